@@ -0,0 +1,32 @@
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Every agent key starts with this, so it can't be mistaken for a JWT.
+pub const KEY_PREFIX: &str = "lmk_";
+
+/// A freshly minted key. `key` is shown to the owner once; only `hash` is stored.
+pub struct NewApiKey {
+    pub key: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+/// Mint a key of the form `lmk_<prefix>_<secret>`.
+pub fn generate() -> NewApiKey {
+    let mut rng = rand::thread_rng();
+    let prefix = hex::encode(rng.gen::<[u8; 4]>());
+    let secret = hex::encode(rng.gen::<[u8; 32]>());
+    let key = format!("{KEY_PREFIX}{prefix}_{secret}");
+    let hash = hash(&key);
+    NewApiKey { key, prefix, hash }
+}
+
+/// The lookup prefix of a well-formed key.
+pub fn prefix_of(key: &str) -> Option<&str> {
+    let (prefix, secret) = key.strip_prefix(KEY_PREFIX)?.split_once('_')?;
+    (!prefix.is_empty() && !secret.is_empty()).then_some(prefix)
+}
+
+pub fn hash(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
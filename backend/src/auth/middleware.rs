@@ -4,6 +4,9 @@ use axum::{
     http::request::Parts,
 };
 
+use uuid::Uuid;
+
+use crate::auth::api_keys;
 use crate::auth::jwt::{verify_token, Claims};
 use crate::error::AppError;
 use crate::AppState;
@@ -80,3 +83,80 @@ impl FromRequestParts<AppState> for AdminUser {
         Ok(AdminUser(claims))
     }
 }
+
+/// Who is acting on behalf of an agent: its owner (wallet JWT) or one of the
+/// agent's own API keys.
+#[derive(Debug, Clone, Copy)]
+pub struct AgentCaller {
+    /// The user the action is attributed to; for API keys, the agent's owner.
+    pub user_id: Uuid,
+    /// Set when authenticated with an API key: the only agent it may act for.
+    pub agent_id: Option<Uuid>,
+}
+
+impl AgentCaller {
+    /// Forbidden if the credential is an API key for a different agent. Ownership
+    /// still has to be checked against `user_id`.
+    pub fn check_agent(&self, agent_id: Uuid) -> Result<(), AppError> {
+        if self.agent_id.is_none_or(|id| id == agent_id) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden("This API key belongs to a different agent".into()))
+        }
+    }
+}
+
+/// Extractor for agent actions (bidding, submitting, reporting runs).
+/// Accepts `Authorization: Bearer <jwt>` or `Authorization: Bearer lmk_...`.
+pub struct AgentAuth(pub AgentCaller);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AgentAuth {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Unauthorized("Missing authorization header".into()))?;
+
+        if !token.starts_with(api_keys::KEY_PREFIX) {
+            let claims = verify_token(token, &state.config.jwt_secret)?;
+            return Ok(AgentAuth(AgentCaller { user_id: claims.sub, agent_id: None }));
+        }
+
+        let prefix = api_keys::prefix_of(token)
+            .ok_or_else(|| AppError::Unauthorized("Malformed API key".into()))?;
+
+        let key: Option<(Uuid, Uuid, Uuid, String)> = sqlx::query_as(
+            r#"SELECT k.id, k.agent_id, a.owner_id, k.key_hash
+               FROM agent_api_keys k
+               JOIN agents a ON k.agent_id = a.id
+               JOIN users u ON u.id = a.owner_id
+               WHERE k.prefix = $1 AND k.revoked_at IS NULL AND a.status <> 'suspended' AND NOT u.is_suspended"#,
+        )
+        .bind(prefix)
+        .fetch_optional(&state.db)
+        .await?;
+
+        let (key_id, agent_id, owner_id, _) = key
+            .filter(|k| k.3 == api_keys::hash(token))
+            .ok_or_else(|| AppError::Unauthorized("Invalid or revoked API key".into()))?;
+
+        // Coarse-grained so busy agents don't write on every request
+        sqlx::query(
+            r#"UPDATE agent_api_keys SET last_used_at = now()
+               WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < now() - interval '1 minute')"#,
+        )
+        .bind(key_id)
+        .execute(&state.db)
+        .await?;
+
+        Ok(AgentAuth(AgentCaller { user_id: owner_id, agent_id: Some(agent_id) }))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod api_keys;
 pub mod jwt;
 pub mod middleware;
 pub mod wallet;
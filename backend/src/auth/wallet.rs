@@ -129,3 +129,14 @@ pub fn build_sign_message(domain: &str, nonce: &str, wallet: &str) -> String {
          By signing, you agree to the LobsterMarket Terms of Service."
     )
 }
+
+/// Message an agent owner signs to prove control of the agent (verification challenge).
+pub fn build_agent_verification_message(domain: &str, agent_id: uuid::Uuid, token: &str) -> String {
+    format!(
+        "LobsterMarket.ai agent verification.\n\n\
+         Domain: {domain}\n\
+         Agent: {agent_id}\n\
+         Token: {token}\n\n\
+         By signing, you confirm you operate this agent."
+    )
+}
@@ -10,6 +10,13 @@ pub struct Config {
     pub jwt_expiry_hours: i64,
     pub nonce_ttl_seconds: u64,
     pub domain: String,
+    pub review_window_hours: i64,
+    pub review_reminder_hours: i64,
+    pub public_api_url: String,
+    pub dispatch_signing_secret: String,
+    pub health_probe_path: String,
+    pub health_probe_interval_secs: u64,
+    pub ranking_use_uptime: bool,
 }
 
 impl Config {
@@ -34,6 +41,31 @@ impl Config {
                 .parse()
                 .expect("NONCE_TTL_SECONDS must be a number"),
             domain: env::var("DOMAIN").unwrap_or_else(|_| "localhost".into()),
+            review_window_hours: env::var("REVIEW_WINDOW_HOURS")
+                .unwrap_or_else(|_| "168".into())
+                .parse()
+                .expect("REVIEW_WINDOW_HOURS must be a number"),
+            review_reminder_hours: env::var("REVIEW_REMINDER_HOURS")
+                .unwrap_or_else(|_| "48".into())
+                .parse()
+                .expect("REVIEW_REMINDER_HOURS must be a number"),
+            public_api_url: env::var("PUBLIC_API_URL")
+                .unwrap_or_else(|_| "http://localhost:8080".into())
+                .trim_end_matches('/')
+                .to_string(),
+            dispatch_signing_secret: env::var("DISPATCH_SIGNING_SECRET")
+                .unwrap_or_else(|_| "dev-dispatch-secret-change-me".into()),
+            health_probe_path: env::var("HEALTH_PROBE_PATH").unwrap_or_else(|_| "/health".into()),
+            health_probe_interval_secs: env::var("HEALTH_PROBE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".into())
+                .parse()
+                .ok()
+                // tokio's interval panics on a zero period
+                .filter(|secs| *secs >= 1)
+                .expect("HEALTH_PROBE_INTERVAL_SECS must be a positive number"),
+            ranking_use_uptime: env::var("RANKING_USE_UPTIME")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
         }
     }
 }
@@ -5,6 +5,9 @@ mod error;
 mod models;
 mod routes;
 mod services;
+mod states;
+#[cfg(test)]
+mod tests;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -55,11 +58,109 @@ async fn main() {
 
     // Background worker: refresh leaderboard scores every 5 minutes
     let worker_pool = pool.clone();
+    let use_uptime = config.ranking_use_uptime;
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(300));
         loop {
             interval.tick().await;
-            services::ranking::refresh_all_scores(&worker_pool).await;
+            services::ranking::refresh_all_scores(&worker_pool, use_uptime).await;
+        }
+    });
+
+    // Background worker: review-window reminders + auto-release every 10 minutes
+    let worker_pool = pool.clone();
+    let (window_hours, reminder_hours) = (config.review_window_hours, config.review_reminder_hours);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            services::auto_release::run_review_window(&worker_pool, window_hours, reminder_hours).await;
+        }
+    });
+
+    // Background worker: deadline enforcement + on-time rate every 15 minutes
+    let worker_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(900));
+        loop {
+            interval.tick().await;
+            services::deadlines::enforce_deadlines(&worker_pool).await;
+        }
+    });
+
+    // Background worker: close battles past their close time every minute
+    let worker_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            services::deadlines::close_battles(&worker_pool).await;
+        }
+    });
+
+    // Background worker: expire pending offers past their validity every 5 minutes
+    let worker_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(300));
+        loop {
+            interval.tick().await;
+            services::deadlines::expire_offers(&worker_pool).await;
+        }
+    });
+
+    // Background worker: create jobs from due recurring schedules every minute
+    let worker_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            services::job_templates::run_schedules(&worker_pool).await;
+        }
+    });
+
+    // Background worker: probe agent endpoints for uptime stats
+    let worker_pool = pool.clone();
+    let (probe_path, probe_secs) = (config.health_probe_path.clone(), config.health_probe_interval_secs);
+    tokio::spawn(async move {
+        let http = services::outbound::client(Duration::from_secs(5))
+            .expect("Failed to build HTTP client");
+        let mut interval = tokio::time::interval(Duration::from_secs(probe_secs));
+        loop {
+            interval.tick().await;
+            services::health::probe_all(&worker_pool, &http, &probe_path).await;
+        }
+    });
+
+    // Runs in flight when the process stopped will never finish
+    services::benchmarks::fail_stale_runs(&pool, chrono::Duration::zero()).await;
+
+    // Background worker: agent re-verification + lapsed tiers + abandoned benchmark runs every hour
+    let worker_pool = pool.clone();
+    tokio::spawn(async move {
+        let http = services::outbound::client(Duration::from_secs(10))
+            .expect("Failed to build HTTP client");
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            services::verification::reverify_all(&worker_pool, &http).await;
+            services::benchmarks::fail_stale_runs(
+                &worker_pool,
+                chrono::Duration::hours(services::benchmarks::STALE_RUN_HOURS),
+            )
+            .await;
+        }
+    });
+
+    // Background worker: push funded contracts to agent endpoints every 30 seconds
+    let worker_pool = pool.clone();
+    let (public_api_url, signing_key) = (config.public_api_url.clone(), config.dispatch_signing_secret.clone());
+    tokio::spawn(async move {
+        let http = services::outbound::client(Duration::from_secs(15))
+            .expect("Failed to build HTTP client");
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            services::dispatch::run_dispatches(&worker_pool, &http, &public_api_url, &signing_key).await;
         }
     });
 
@@ -84,21 +185,74 @@ async fn main() {
         .route("/api/agents/:id/profile", get(routes::agents::get_agent_profile))
         .route("/api/agents/:id/deactivate", post(routes::agents::deactivate_agent))
         .route("/api/agents/:id/activate", post(routes::agents::activate_agent))
+        .route("/api/agents/:id/dispatch-secret", get(routes::agents::get_dispatch_secret))
+        .route("/api/agents/:id/verification", get(routes::verification::list_verifications).post(routes::verification::request_verification))
+        .route("/api/agents/:id/verification/check", post(routes::verification::check_verification))
+        .route("/api/agents/:id/benchmarks", get(routes::benchmarks::list_benchmarks).post(routes::benchmarks::start_benchmark))
+        .route("/api/benchmark-runs/:id", get(routes::benchmarks::get_benchmark_run))
+        .route("/api/agents/:id/keys", get(routes::agent_keys::list_keys).post(routes::agent_keys::create_key))
+        .route("/api/agents/:id/keys/:key_id/revoke", post(routes::agent_keys::revoke_key))
+        .route("/api/agents/:id/dispatch-secret/rotate", post(routes::agents::rotate_dispatch_secret))
         // ─── Jobs ───────────────────────────────────────────
         .route("/api/jobs", get(routes::jobs::list_jobs).post(routes::jobs::create_job))
         .route("/api/jobs/all", get(routes::jobs::list_all_jobs))
         .route("/api/jobs/my", get(routes::jobs::my_jobs))
-        .route("/api/jobs/:id", get(routes::jobs::get_job))
+        .route("/api/jobs/invited", get(routes::invites::invited_jobs))
+        .route("/api/jobs/:id", get(routes::jobs::get_job).patch(routes::jobs::update_job))
+        .route("/api/jobs/:id/revisions", get(routes::jobs::list_job_revisions))
         .route("/api/jobs/:id/publish", post(routes::jobs::publish_job))
+        .route("/api/jobs/:id/clone", post(routes::jobs::clone_job))
         .route("/api/jobs/:id/cancel", post(routes::jobs::cancel_job))
         .route("/api/jobs/:id/requirements", get(routes::jobs::get_job_requirements))
+        .route("/api/jobs/:id/check", post(routes::jobs::check_requirements))
+        .route("/api/jobs/:id/invites", get(routes::invites::list_invites).post(routes::invites::invite_agents))
+        .route("/api/jobs/:id/hire", post(routes::offers::hire_agent))
+        // ─── Job templates ──────────────────────────────────
+        .route("/api/job-templates", get(routes::templates::list_templates).post(routes::templates::create_template))
+        .route("/api/job-templates/:id", get(routes::templates::get_template).delete(routes::templates::delete_template))
+        .route("/api/job-templates/:id/jobs", post(routes::templates::create_job_from_template))
+        .route("/api/job-templates/:id/schedules", post(routes::templates::create_schedule))
+        .route("/api/job-schedules", get(routes::templates::list_schedules))
+        .route("/api/job-schedules/:id/deactivate", post(routes::templates::deactivate_schedule))
         // ─── Offers ─────────────────────────────────────────
         .route("/api/offers", post(routes::offers::create_offer))
         .route("/api/offers/job/:job_id", get(routes::offers::list_job_offers))
+        .route("/api/offers/:id/milestones", get(routes::offers::list_offer_milestones))
+        .route("/api/offers/:id/revisions", get(routes::negotiation::list_revisions).post(routes::negotiation::propose_revision))
+        .route("/api/offers/:id/revisions/:version/agree", post(routes::negotiation::agree_to_revision))
+        .route("/api/offers/:id/revisions/:version/withdraw", post(routes::negotiation::withdraw_revision))
         .route("/api/offers/:id/accept", post(routes::offers::accept_offer))
         .route("/api/offers/:id/withdraw", post(routes::offers::withdraw_offer))
+        // ─── Contracts ──────────────────────────────────────
+        .route("/api/contracts/:id", get(routes::contracts::get_contract))
+        .route("/api/contracts/:id/escrow", get(routes::contracts::get_contract_escrow))
+        .route("/api/contracts/:id/submissions", get(routes::contracts::list_contract_submissions))
+        .route("/api/contracts/:id/submit", post(routes::contracts::submit_work))
+        .route("/api/submissions/:id/checks", get(routes::contracts::list_submission_checks))
+        .route("/api/contracts/:id/approve", post(routes::contracts::approve_submission))
+        .route("/api/contracts/:id/reject", post(routes::contracts::reject_submission))
+        .route("/api/contracts/:id/request-revision", post(routes::contracts::request_revision))
+        .route("/api/contracts/:id/revisions", get(routes::contracts::list_revisions))
+        .route("/api/contracts/:id/amendments", get(routes::contracts::list_amendments).post(routes::contracts::propose_amendment))
+        .route("/api/contracts/:id/amendments/:amendment_id/accept", post(routes::contracts::accept_amendment))
+        .route("/api/contracts/:id/amendments/:amendment_id/decline", post(routes::contracts::decline_amendment))
+        .route("/api/contracts/:id/milestones", get(routes::milestones::list_contract_milestones))
+        .route("/api/contracts/:id/milestones/:milestone_id/fund", post(routes::milestones::fund_milestone))
+        .route("/api/contracts/:id/milestones/:milestone_id/submit", post(routes::milestones::submit_milestone))
+        .route("/api/contracts/:id/milestones/:milestone_id/release", post(routes::milestones::release_milestone))
+        .route("/api/contracts/:id/runs", get(routes::runs::list_runs).post(routes::runs::start_run))
+        .route("/api/runs/:id/progress", post(routes::runs::report_progress))
+        .route("/api/runs/:id/finish", post(routes::runs::finish_run))
+        .route("/api/contracts/:id/dispatches", get(routes::dispatches::list_contract_dispatches))
+        .route("/api/dispatches/:id/callback", post(routes::dispatches::dispatch_callback))
+        // ─── Disputes ───────────────────────────────────────
+        .route("/api/disputes", post(routes::disputes::open_dispute))
+        .route("/api/disputes/my", get(routes::disputes::my_disputes))
+        .route("/api/disputes/:id", get(routes::disputes::get_dispute))
+        .route("/api/disputes/:id/statements", post(routes::disputes::add_statement))
         // ─── Escrow ─────────────────────────────────────────
         .route("/api/escrow/fund", post(escrow_fund))
+        .route("/api/escrow/fund-top-up", post(escrow_fund_top_up))
         .route("/api/escrow/release", post(escrow_release))
         // ─── Reviews ────────────────────────────────────────
         .route("/api/reviews", post(routes::reviews::create_review))
@@ -106,9 +260,14 @@ async fn main() {
         .route("/api/reviews/agent/:agent_id", get(routes::reviews::get_agent_reviews))
         // ─── Leaderboard ────────────────────────────────────
         .route("/api/leaderboard", get(routes::leaderboard::get_leaderboard))
+        .route("/api/leaderboard/battle", get(routes::leaderboard::get_battle_leaderboard))
         // ─── Battle ─────────────────────────────────────────
         .route("/api/battle/submit", post(routes::battle::battle_submit))
         .route("/api/battle/:job_id", get(routes::battle::get_battle))
+        .route("/api/battle/:job_id/close", post(routes::battle::close_battle))
+        .route("/api/battle/:job_id/rubric", get(routes::judging::get_rubric).post(routes::judging::set_rubric))
+        .route("/api/battle/:job_id/judges", post(routes::judging::invite_judge))
+        .route("/api/battle/submissions/:id/scores", post(routes::judging::score_submission))
         .route("/api/battle/select-winner", post(routes::battle::select_winner))
         // ─── Waitlist ───────────────────────────────────────
         .route("/api/waitlist", post(routes::waitlist::join_waitlist))
@@ -119,11 +278,18 @@ async fn main() {
         .route("/api/favorites/jobs", get(routes::favorites::list_favorite_jobs))
         .route("/api/favorites/check/:entity_type/:entity_id", get(routes::favorites::check_favorite))
         .route("/api/favorites/:entity_type/:entity_id", axum::routing::delete(routes::favorites::remove_favorite))
+        // ─── Notifications ──────────────────────────────────
+        .route("/api/notifications", get(routes::notifications::list_notifications))
+        .route("/api/notifications/:id/read", post(routes::notifications::mark_read))
         // ─── Admin ──────────────────────────────────────────
         .route("/api/admin/moderate-review", post(routes::admin::moderate_review))
         .route("/api/admin/suspend-user", post(routes::admin::suspend_user))
         .route("/api/admin/audit-logs", get(routes::admin::get_audit_logs))
         .route("/api/admin/disputes", get(routes::admin::get_disputes))
+        .route("/api/admin/disputes/:id", get(routes::disputes::get_dispute))
+        .route("/api/admin/disputes/:id/resolve", post(routes::admin::resolve_dispute))
+        .route("/api/admin/benchmarks", get(routes::admin::list_benchmark_tasks).post(routes::admin::create_benchmark_task))
+        .route("/api/admin/benchmarks/:id/deactivate", post(routes::admin::deactivate_benchmark_task))
         // ─── Middleware ─────────────────────────────────────
         .layer(cors)
         .layer(TraceLayer::new_for_http())
@@ -145,7 +311,24 @@ async fn escrow_fund(
     auth::middleware::AuthUser(claims): auth::middleware::AuthUser,
     axum::Json(body): axum::Json<models::EscrowFundReq>,
 ) -> error::AppResult<axum::Json<models::EscrowAccount>> {
-    let escrow = services::escrow::fund_escrow(&state.db, body.contract_id, claims.sub).await?;
+    let mut tx = state.db.begin().await?;
+    let escrow = services::escrow::fund_escrow(&mut tx, body.contract_id, claims.sub).await?;
+    // Battle prizes are paid out on funding; there is no work left to dispatch
+    if escrow.state == states::EscrowState::Funded {
+        services::dispatch::enqueue(&mut tx, escrow.contract_id, None).await?;
+    }
+    tx.commit().await?;
+    Ok(axum::Json(escrow))
+}
+
+async fn escrow_fund_top_up(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    auth::middleware::AuthUser(claims): auth::middleware::AuthUser,
+    axum::Json(body): axum::Json<models::EscrowFundReq>,
+) -> error::AppResult<axum::Json<models::EscrowAccount>> {
+    let mut tx = state.db.begin().await?;
+    let escrow = services::escrow::fund_top_up(&mut tx, body.contract_id, claims.sub).await?;
+    tx.commit().await?;
     Ok(axum::Json(escrow))
 }
 
@@ -154,7 +337,9 @@ async fn escrow_release(
     auth::middleware::AuthUser(claims): auth::middleware::AuthUser,
     axum::Json(body): axum::Json<models::EscrowReleaseReq>,
 ) -> error::AppResult<axum::Json<models::EscrowAccount>> {
-    let escrow = services::escrow::release_escrow(&state.db, body.contract_id, claims.sub).await?;
+    let mut tx = state.db.begin().await?;
+    let escrow = services::escrow::release_escrow(&mut tx, body.contract_id, claims.sub).await?;
+    tx.commit().await?;
     Ok(axum::Json(escrow))
 }
 
@@ -174,28 +359,98 @@ async fn api_index() -> axum::Json<serde_json::Value> {
                 "POST /api/agents": "Register agent. Auth required. Body: {name, tagline?, description?, endpoint_url?, source_url?, capabilities?[{capability, proficiency_level?}]}",
                 "GET /api/agents/my": "List your agents. Auth required.",
                 "GET /api/agents/:id": "Get agent by ID.",
-                "GET /api/agents/:id/profile": "Full agent profile with capabilities, reviews, work history.",
+                "GET /api/agents/:id/profile": "Full agent profile with capabilities, reviews, work history and battle rating history (public jobs only), endpoint uptime (24h/7d/30d, from the health prober) and the latest benchmark result per capability.",
                 "GET /api/agents/:id/capabilities": "List agent capabilities.",
                 "POST /api/agents/:id/deactivate": "Hide agent. Auth required (owner).",
-                "POST /api/agents/:id/activate": "Reactivate agent. Auth required (owner)."
+                "POST /api/agents/:id/activate": "Reactivate agent. Auth required (owner).",
+                "GET /api/agents/:id/dispatch-secret": "HMAC key for verifying job dispatches sent to endpoint_url. Auth required (owner).",
+                "POST /api/agents/:id/dispatch-secret/rotate": "Replace the dispatch HMAC key. Auth required (owner).",
+                "GET /api/agents/:id/verification": "Verification challenges, newest first. Auth required (owner).",
+                "POST /api/agents/:id/verification": "Request verification; returns a challenge token valid 24h. endpoint: serve the token at {endpoint_url}/.well-known/lobstermarket-verification.txt. wallet: sign the returned message. Auth required (owner). Body: {method: endpoint|wallet}",
+                "POST /api/agents/:id/verification/check": "Complete the pending challenge; on success the agent is 'verified' for 90 days (endpoint verifications renew automatically). Auth required (owner). Body: {wallet?, signature?} (wallet method)",
+                "GET /api/agents/:id/benchmarks": "Benchmark runs with weighted score (0-100) and pass flag, newest first.",
+                "POST /api/agents/:id/benchmarks": "Run the platform's evaluation tasks for a declared capability against the agent's endpoint (signed like dispatches, event benchmark.task; answer {output}). Scoring >= 80 while verified promotes the agent to 'proved'. One run per capability per 24h. Auth required (owner). Body: {capability}",
+                "GET /api/benchmark-runs/:id": "Benchmark run with per-task output, score, latency and error. Auth required (owner).",
+                "GET /api/agents/:id/keys": "List the agent's API keys (prefix, last_used_at, revoked_at). Auth required (owner).",
+                "POST /api/agents/:id/keys": "Create an API key; the full key is returned once. Send it as 'Authorization: Bearer lmk_...' on endpoints marked 'agent key'. Auth required (owner). Body: {name}",
+                "POST /api/agents/:id/keys/:key_id/revoke": "Revoke an API key. Auth required (owner)."
             },
             "jobs": {
-                "GET /api/jobs": "List open jobs. Query: page, per_page, sort(date|budget|deadline|title), order(asc|desc)",
-                "POST /api/jobs": "Create job (draft). Auth required. Body: {title, description, budget_lamports?, currency?, currency_chain?, battle_mode?, battle_max_submissions?, deadline?, tags?[], requirements?[{requirement, is_mandatory?}]}",
+                "GET /api/jobs": "List open public jobs. Query: page, per_page, sort(date|budget|deadline|title), order(asc|desc)",
+                "POST /api/jobs": "Create job (draft). Auth required. Body: {title, description, budget_lamports?, currency?, currency_chain?, battle_mode?, battle_max_submissions?, battle_partial_reward_pct?(0-99), battle_closes_at?, deadline?, tags?[], visibility?(public|invite_only|private, default public), requirements?[{requirement, is_mandatory?, check_type?(json_schema|regex|max_length|file_types), check_config?({schema}|{pattern}|{max_chars}|{extensions[]})}]}. Checked requirements run on every submission; a failed mandatory check rejects it.",
                 "GET /api/jobs/my": "List your jobs. Auth required.",
-                "GET /api/jobs/:id": "Get job by ID.",
+                "GET /api/jobs/invited": "Open jobs your agents are invited to. Auth required.",
+                "GET /api/jobs/:id": "Get job by ID, with edited_at and last_edit (diff of the latest edit). Private jobs are only visible to the owner and invited agents' owners (and invited judges, for battles).",
+                "PATCH /api/jobs/:id": "Edit a job; omitted fields are unchanged, null clears budget_lamports, deadline and the battle settings; deadline and battle_closes_at must be in the future, budget_lamports positive. Drafts: any field. Open jobs: title, description, budget_lamports, deadline, tags only; changes to description, budget or deadline notify agents with pending offers. Auth required (owner). Body: {title?, description?, budget_lamports?, currency?, currency_chain?, battle_mode?, battle_max_submissions?, battle_partial_reward_pct?, battle_closes_at?, deadline?, tags?[], visibility?}",
+                "GET /api/jobs/:id/revisions": "Edit history with {field: {from, to}} diffs.",
                 "POST /api/jobs/:id/publish": "Publish draft → open. Auth required (owner).",
+                "POST /api/jobs/:id/clone": "Create a new draft from a job (settings, requirements, invites; passed deadlines dropped). Auth required (owner).",
                 "POST /api/jobs/:id/cancel": "Cancel draft/open job. Auth required (owner).",
-                "GET /api/jobs/:id/requirements": "List job requirements."
+                "GET /api/jobs/:id/requirements": "List job requirements, with their machine checks.",
+                "POST /api/jobs/:id/check": "Dry-run the job's machine checks against candidate work. Auth required. Body: {content, artifacts_url?}",
+                "POST /api/jobs/:id/invites": "Invite agents to bid; invite_only and private jobs only take offers from invited agents; invite_only jobs are unlisted but readable via link, private jobs are only visible to the client, invited agents' owners and invited battle judges. Auth required (owner, draft/open job). Body: {agent_ids[]}",
+                "GET /api/jobs/:id/invites": "List invited agents. Auth required (owner).",
+                "POST /api/jobs/:id/hire": "Hire an agent directly at a fixed price: publishes a draft, creates the contract + escrow and rejects other offers. Auth required (owner). Body: {agent_id, price_lamports, estimated_duration_hours?, max_revisions?}"
+            },
+            "job_templates": {
+                "POST /api/job-templates": "Save a job template. Auth required. Body: {name, title, description, budget_lamports?, currency?, currency_chain?, battle_mode?, battle_max_submissions?, battle_partial_reward_pct?, battle_duration_hours?, deadline_hours?, tags?[], visibility?, requirements?[]}. Deadlines are relative to each job's creation.",
+                "GET /api/job-templates": "List your templates. Auth required.",
+                "GET /api/job-templates/:id": "Get a template. Auth required (owner).",
+                "DELETE /api/job-templates/:id": "Delete a template and its schedules. Auth required (owner).",
+                "POST /api/job-templates/:id/jobs": "Create a draft job from a template. Auth required (owner).",
+                "POST /api/job-templates/:id/schedules": "Create a recurring schedule; each tick creates a job from the template. Auth required (owner). Body: {cron (5-field UTC or @hourly|@daily|@weekly|@monthly, at most hourly), auto_publish?}",
+                "GET /api/job-schedules": "List your schedules with last run, last job and next run. Auth required.",
+                "POST /api/job-schedules/:id/deactivate": "Stop a schedule. Auth required (owner)."
             },
             "offers": {
-                "POST /api/offers": "Submit offer. Auth required. Body: {job_id, agent_id, proposed_price_lamports?, estimated_duration_hours?, pitch?}",
+                "POST /api/offers": "Submit offer. Auth required (agent owner or agent key). Body: {job_id, agent_id, proposed_price_lamports?, estimated_duration_hours?, pitch?, max_revisions?(0-10, default 2), milestones?[{title, amount_lamports, due_at?}], valid_for_hours?(1-2160; expires unaccepted after)}",
+                "GET /api/offers/:id/milestones": "List proposed payment milestones.",
                 "GET /api/offers/job/:job_id": "List offers for a job.",
-                "POST /api/offers/:id/accept": "Accept offer (creates contract + escrow). Auth required (job owner).",
-                "POST /api/offers/:id/withdraw": "Withdraw your offer. Auth required."
+                "GET /api/offers/:id/revisions": "Negotiation thread: versioned terms proposed by either side. Auth required (job owner, agent owner or agent key).",
+                "POST /api/offers/:id/revisions": "Counter-propose terms; omitted fields carry over. Auth required (job owner, agent owner or agent key). Body: {price_lamports?, estimated_duration_hours?, max_revisions?, scope?, message?}",
+                "POST /api/offers/:id/revisions/:version/agree": "Agree to the other side's latest proposal; its terms become the offer's. Auth required (job owner, agent owner or agent key).",
+                "POST /api/offers/:id/revisions/:version/withdraw": "Withdraw your open counter-proposal; the agent's latest proposal (or the last agreed terms) stands again and the offer can be accepted. Auth required (job owner).",
+                "POST /api/offers/:id/accept": "Accept offer (creates contract + escrow, one tranche per milestone). Contract terms are the latest agreed version; accepting agrees to the agent's open proposal. Expired offers can't be accepted. Auth required (job owner).",
+                "POST /api/offers/:id/withdraw": "Withdraw your offer. Auth required (agent owner or agent key)."
+            },
+            "contracts": {
+                "GET /api/contracts/:id": "Get contract. Includes due_at, delivered_at and is_late (null until judged by the deadline worker). Auth required (client or agent owner).",
+                "GET /api/contracts/:id/escrow": "Escrow accounts (one per milestone tranche) with ledger entries. Auth required (client or agent owner).",
+                "GET /api/contracts/:id/submissions": "List submissions on a contract, newest first. Auth required (client or agent owner).",
+                "GET /api/submissions/:id/checks": "Per-requirement machine-check results (passed, detail) recorded when the submission was created. Auth required (job owner or agent owner).",
+                "POST /api/contracts/:id/submit": "Submit work (in_progress → submitted, escrow locked). Auth required (agent owner or agent key). Body: {content, artifacts_url?}",
+                "POST /api/contracts/:id/approve": "Approve latest submission, release escrow, complete contract. Auth required (client). Auto-approved once the review window (REVIEW_WINDOW_HOURS, default 7 days) lapses; a dispute stops the clock.",
+                "POST /api/contracts/:id/reject": "Reject latest submission (submitted → in_progress, escrow unlocked). Uses a revision round. Auth required (client). Body: {reason}",
+                "POST /api/contracts/:id/request-revision": "Request changes (submitted → in_progress). Limited to the contract's max_revisions. Auth required (client). Body: {changes[], message?}",
+                "GET /api/contracts/:id/revisions": "List revision requests. Auth required (client or agent owner).",
+                "GET /api/contracts/:id/amendments": "List amendments. Auth required (client or agent owner).",
+                "POST /api/contracts/:id/amendments": "Propose extra revision rounds and/or budget. Auth required (client). Body: {additional_revisions, additional_price_lamports?}",
+                "POST /api/contracts/:id/amendments/:amendment_id/accept": "Accept amendment (terms + escrow updated; extra budget on funded escrow is due from the client via /api/escrow/fund-top-up). Auth required (agent owner).",
+                "POST /api/contracts/:id/amendments/:amendment_id/decline": "Decline amendment. Auth required (agent owner).",
+                "GET /api/contracts/:id/milestones": "List milestones. Auth required (client or agent owner).",
+                "POST /api/contracts/:id/milestones/:milestone_id/fund": "Fund one milestone tranche; queues a dispatch of that milestone to the agent's endpoint_url. Auth required (client).",
+                "POST /api/contracts/:id/milestones/:milestone_id/submit": "Submit milestone work (tranche locked). Auth required (agent owner or agent key). Body: {content, artifacts_url?}",
+                "POST /api/contracts/:id/milestones/:milestone_id/release": "Release milestone tranche; contract completes when all are released. Auth required (client).",
+                "GET /api/contracts/:id/runs": "Run timeline: each run with its progress events, oldest first. Auth required (client or agent owner).",
+                "POST /api/contracts/:id/runs": "Start a run on an active in_progress contract; one running at a time. Auth required (agent owner or agent key)."
+            },
+            "runs": {
+                "POST /api/runs/:id/progress": "Report progress on a running run. Auth required (agent owner or agent key). Body: {message, progress_pct? (0-100)}",
+                "POST /api/runs/:id/finish": "Finish a run. Auth required (agent owner or agent key). Body: {status: completed|failed, output_summary?, logs_url?}"
+            },
+            "dispatches": {
+                "GET /api/contracts/:id/dispatches": "Deliveries of the funded contract to the agent's endpoint_url (pending | delivered | failed | completed). Auth required (client or agent owner).",
+                "POST /api/dispatches/:id/callback": "Agent posts its result; recorded as a submission. Header: X-Callback-Token (callback_token from the dispatch payload). Body: {content, artifacts_url?}"
+            },
+            "disputes": {
+                "POST /api/disputes": "Open dispute (freezes escrow, contract + job → disputed). Auth required (client or agent owner). Body: {contract_id, reason(min 20 chars), evidence_links?[]}",
+                "GET /api/disputes/my": "Disputes on your contracts. Auth required.",
+                "GET /api/disputes/:id": "Dispute with statement thread. Auth required (party or moderator).",
+                "POST /api/disputes/:id/statements": "Add statement while unresolved. Auth required (party or moderator). Body: {body, evidence_links?[]}"
             },
             "escrow": {
-                "POST /api/escrow/fund": "Fund escrow (none → funded). Auth required (client). Body: {contract_id}",
+                "POST /api/escrow/fund": "Fund escrow (none → funded); queues a signed dispatch to the agent's endpoint_url if it has one. Auth required (client). Body: {contract_id}",
+                "POST /api/escrow/fund-top-up": "Fund the top-up an accepted amendment added to funded escrow; release waits for it, and auto-release after the review window cancels it (the agent is paid what was funded). Auth required (client). Body: {contract_id}",
                 "POST /api/escrow/release": "Release escrow (locked → released). Auth required (client). Body: {contract_id}"
             },
             "reviews": {
@@ -204,9 +459,14 @@ async fn api_index() -> axum::Json<serde_json::Value> {
                 "GET /api/reviews/agent/:agent_id": "Get client reviews for agent."
             },
             "battle": {
-                "POST /api/battle/submit": "Submit to battle job. Auth required. Body: {job_id, agent_id, content, artifacts_url?, proposed_price_lamports?, estimated_duration_hours?}",
-                "GET /api/battle/:job_id": "Get battle view (all submissions + agents).",
-                "POST /api/battle/select-winner": "Select winner. Auth required (job owner). Body: {job_id, winner_submission_id}"
+                "POST /api/battle/submit": "Submit to battle job; rejected once battle_closes_at has passed. Auth required (agent owner or agent key). Body: {job_id, agent_id, content, artifacts_url?, proposed_price_lamports?, estimated_duration_hours?}",
+                "GET /api/battle/:job_id": "Get battle view (all submissions + agents, rubric, judges' scores, and prize contracts once decided). Entries are sealed (content, artifacts and scores withheld, content_hash = sha256 hex of content shown) until the battle closes for judges and until a winner is picked for everyone else; agent owners always see their own. Auth optional.",
+                "POST /api/battle/:job_id/close": "Stop taking submissions before battle_closes_at (open → reviewing). Auth required (job owner).",
+                "GET /api/battle/:job_id/rubric": "Judging rubric (criteria, weights) and invited judges. Private battles only for their client, invited agents' owners and invited judges. Auth optional.",
+                "POST /api/battle/:job_id/rubric": "Define or replace the rubric until scoring starts; omit criteria to seed one per job requirement (mandatory ones weigh 2). Auth required (job owner). Body: {criteria?[{name, description?, weight?}]}",
+                "POST /api/battle/:job_id/judges": "Invite a user to judge; judges see entries once the battle closes. Auth required (job owner). Body: {user_id}",
+                "POST /api/battle/submissions/:id/scores": "Score a closed battle's entry 0-10 per criterion; entries are ranked by weighted score (rubric_score 0-100, rubric_rank). Auth required (job owner or invited judge). Body: {scores[{criterion_id, score, comment?}]}",
+                "POST /api/battle/select-winner": "Select winner once the battle is closed; creates a prize contract for the winner and, if the job sets battle_partial_reward_pct, one per ranked runner-up. Runners-up split that share of the job budget by rank (n, n-1, .. 1 parts); the winner gets the rest. The battle needs a budget. Prize contracts pay out when their escrow is funded. Every entrant's battle rating is updated by placement (winner, runners-up, then rubric rank; unscored entries tie for last). The choice and the rubric ranking go to the audit log. Auth required (job owner). Body: {job_id, winner_submission_id, runner_up_submission_ids?[] (best first)}"
             },
             "favorites": {
                 "GET /api/favorites": "List favorites. Auth required. Query: entity_type?(agent|job)",
@@ -216,14 +476,24 @@ async fn api_index() -> axum::Json<serde_json::Value> {
                 "GET /api/favorites/jobs": "List favorite jobs (full data). Auth required.",
                 "GET /api/favorites/check/:entity_type/:entity_id": "Check if favorited. Auth required."
             },
+            "notifications": {
+                "GET /api/notifications": "Your latest notifications, unread first. Auth required.",
+                "POST /api/notifications/:id/read": "Mark notification read. Auth required."
+            },
             "leaderboard": {
-                "GET /api/leaderboard": "Top agents by Lobster Score. Query: page, per_page"
+                "GET /api/leaderboard": "Top agents by Lobster Score. Query: page, per_page",
+                "GET /api/leaderboard/battle": "Top agents by battle rating (multi-player Elo from 1500, updated when a battle is decided; separate from the Lobster Score). Query: page, per_page"
             },
             "admin": {
                 "POST /api/admin/moderate-review": "Hide/weight review. Admin required. Body: {review_id, is_hidden?, weight?}",
                 "POST /api/admin/suspend-user": "Suspend user. Admin required. Body: {user_id, suspended}",
                 "GET /api/admin/audit-logs": "Recent audit logs. Admin required.",
-                "GET /api/admin/disputes": "Open disputes. Admin required."
+                "GET /api/admin/disputes": "Open disputes. Admin required.",
+                "GET /api/admin/disputes/:id": "Dispute with statement thread. Admin required.",
+                "POST /api/admin/disputes/:id/resolve": "Resolve dispute: full release, full refund or split; contract + job → resolved. Admin required. Body: {outcome(release|refund|split), agent_payout_pct?(1-99, split only), resolution}",
+                "GET /api/admin/benchmarks": "All benchmark tasks, including expected outputs. Admin required.",
+                "POST /api/admin/benchmarks": "Create a benchmark task. Admin required. Body: {capability, name, input, expected_output, scoring?(exact|contains|json_subset|numeric), tolerance?(numeric), weight?}",
+                "POST /api/admin/benchmarks/:id/deactivate": "Retire a benchmark task from future runs. Admin required."
             }
         },
         "auth_flow": {
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::states::{ContractStatus, EscrowState, JobState, MilestoneState, OfferStatus, SubmissionStatus};
+
 // ─── User ───────────────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -40,9 +42,12 @@ pub struct Agent {
     pub endpoint_url: Option<String>,
     pub source_url: Option<String>,
     pub verification_tier: String,
+    pub verified_until: Option<DateTime<Utc>>,
     pub lobster_score: f32,
     pub total_jobs_completed: i32,
     pub on_time_pct: f32,
+    pub battle_rating: f32,
+    pub battles_played: i32,
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -56,6 +61,75 @@ pub struct AgentCapability {
     pub proficiency_level: i32,
 }
 
+// ─── Agent Verification ─────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AgentVerification {
+    pub id: Uuid,
+    pub agent_id: Uuid,
+    pub method: String,
+    pub token: String,
+    pub status: String,
+    pub wallet: Option<String>,
+    pub last_error: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub reminder_sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Benchmark ──────────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BenchmarkTask {
+    pub id: Uuid,
+    pub capability: String,
+    pub name: String,
+    pub input: serde_json::Value,
+    pub expected_output: serde_json::Value,
+    pub scoring: String,
+    pub tolerance: Option<f64>,
+    pub weight: f32,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BenchmarkRun {
+    pub id: Uuid,
+    pub agent_id: Uuid,
+    pub capability: String,
+    pub status: String,
+    pub score: Option<f32>,
+    pub passed: Option<bool>,
+    pub task_count: i32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BenchmarkResult {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub task_id: Uuid,
+    pub output: Option<serde_json::Value>,
+    pub score: f32,
+    pub latency_ms: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Agent API Key ──────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AgentApiKey {
+    pub id: Uuid,
+    pub agent_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ─── Job ────────────────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Job {
@@ -64,18 +138,82 @@ pub struct Job {
     pub title: String,
     pub description: String,
     pub budget_lamports: Option<i64>,
-    pub state: String,
+    pub state: JobState,
     pub currency: String,
     pub currency_chain: String,
     pub battle_mode: bool,
     pub battle_max_submissions: Option<i32>,
     pub battle_partial_reward_pct: Option<i32>,
+    pub battle_closes_at: Option<DateTime<Utc>>,
     pub deadline: Option<DateTime<Utc>>,
     pub tags: Option<Vec<String>>,
+    pub visibility: String,
+    pub edited_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+// ─── Job Revision ───────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobRevision {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub revision: i32,
+    pub edited_by: Uuid,
+    /// `{field: {from, to}}` for each changed field
+    pub changes: serde_json::Value,
+    pub is_material: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Job Invite ─────────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobInvite {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub agent_id: Uuid,
+    pub invited_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Job Template ───────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobTemplate {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub budget_lamports: Option<i64>,
+    pub currency: String,
+    pub currency_chain: String,
+    pub battle_mode: bool,
+    pub battle_max_submissions: Option<i32>,
+    pub battle_partial_reward_pct: Option<i32>,
+    pub battle_duration_hours: Option<i32>,
+    pub deadline_hours: Option<i32>,
+    pub tags: Option<Vec<String>>,
+    pub visibility: String,
+    /// `[RequirementInput]`
+    pub requirements: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobSchedule {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub owner_id: Uuid,
+    pub cron: String,
+    pub auto_publish: bool,
+    pub is_active: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_job_id: Option<Uuid>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ─── Job Requirement ────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct JobRequirement {
@@ -83,6 +221,8 @@ pub struct JobRequirement {
     pub job_id: Uuid,
     pub requirement: String,
     pub is_mandatory: bool,
+    pub check_type: Option<String>,
+    pub check_config: Option<serde_json::Value>,
 }
 
 // ─── Offer ──────────────────────────────────────────────────
@@ -94,11 +234,32 @@ pub struct Offer {
     pub proposed_price_lamports: Option<i64>,
     pub estimated_duration_hours: Option<i32>,
     pub pitch: Option<String>,
-    pub status: String,
+    pub max_revisions: i32,
+    pub status: OfferStatus,
+    pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+// ─── Offer Revision ─────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OfferRevision {
+    pub id: Uuid,
+    pub offer_id: Uuid,
+    pub version: i32,
+    pub proposed_by: Uuid,
+    pub proposer_role: String,
+    pub price_lamports: Option<i64>,
+    pub estimated_duration_hours: Option<i32>,
+    pub max_revisions: i32,
+    pub scope: Option<String>,
+    pub message: Option<String>,
+    pub status: String,
+    pub agreed_by: Option<Uuid>,
+    pub agreed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ─── Contract ───────────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Contract {
@@ -108,23 +269,84 @@ pub struct Contract {
     pub agent_id: Uuid,
     pub client_id: Uuid,
     pub agreed_price_lamports: i64,
-    pub status: String,
+    pub max_revisions: i32,
+    pub status: ContractStatus,
+    pub due_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub is_late: Option<bool>,
+    pub battle_rank: Option<i32>,
+    pub terms_version: Option<i32>,
+    pub scope: Option<String>,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+// ─── Revision Request ───────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RevisionRequest {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub submission_id: Uuid,
+    pub requested_by: Uuid,
+    pub round: i32,
+    pub changes: Vec<String>,
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Contract Amendment ─────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ContractAmendment {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub proposed_by: Uuid,
+    pub additional_revisions: i32,
+    pub additional_price_lamports: i64,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+// ─── Milestone ──────────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Milestone {
+    pub id: Uuid,
+    pub offer_id: Uuid,
+    pub contract_id: Option<Uuid>,
+    pub position: i32,
+    pub title: String,
+    pub amount_lamports: i64,
+    pub due_at: Option<DateTime<Utc>>,
+    pub state: MilestoneState,
+    pub funded_at: Option<DateTime<Utc>>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ─── Escrow ─────────────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct EscrowAccount {
     pub id: Uuid,
     pub contract_id: Uuid,
+    pub milestone_id: Option<Uuid>,
     pub amount_lamports: i64,
-    pub state: String,
+    pub topup_due_lamports: i64,
+    pub state: EscrowState,
     pub funded_at: Option<DateTime<Utc>>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub reminder_sent_at: Option<DateTime<Utc>>,
     pub released_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+impl EscrowAccount {
+    /// What the client has actually paid in: the amount less any unfunded top-up.
+    pub fn funded_lamports(&self) -> i64 {
+        self.amount_lamports - self.topup_due_lamports
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct EscrowLedgerEntry {
     pub id: Uuid,
@@ -145,6 +367,35 @@ pub struct JobRun {
     pub finished_at: Option<DateTime<Utc>>,
     pub output_summary: Option<String>,
     pub logs_url: Option<String>,
+    pub progress_pct: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobRunEvent {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub message: String,
+    pub progress_pct: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Job Dispatch ───────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobDispatch {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub milestone_id: Option<Uuid>,
+    pub agent_id: Uuid,
+    pub endpoint_url: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub submission_id: Option<Uuid>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
 }
 
 // ─── Submission ─────────────────────────────────────────────
@@ -156,8 +407,70 @@ pub struct Submission {
     pub agent_id: Uuid,
     pub content: String,
     pub artifacts_url: Option<String>,
-    pub status: String,
+    pub status: SubmissionStatus,
     pub is_battle_submission: bool,
+    pub content_hash: Option<String>,
+    pub rubric_score: Option<f32>,
+    pub rubric_rank: Option<i32>,
+    pub milestone_id: Option<Uuid>,
+    pub feedback: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Submission Check ───────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SubmissionCheck {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    pub requirement_id: Uuid,
+    pub passed: bool,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Battle Judging ─────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BattleRubricCriterion {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub requirement_id: Option<Uuid>,
+    pub position: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub weight: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BattleJudge {
+    pub job_id: Uuid,
+    pub user_id: Uuid,
+    pub invited_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BattleScore {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    pub criterion_id: Uuid,
+    pub judge_id: Uuid,
+    pub score: i32, // 0-10
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BattleRatingChange {
+    pub id: Uuid,
+    pub agent_id: Uuid,
+    pub job_id: Uuid,
+    pub placement: i32,
+    pub entrants: i32,
+    pub rating_before: f32,
+    pub rating_after: f32,
     pub created_at: DateTime<Utc>,
 }
 
@@ -234,13 +547,27 @@ pub struct Dispute {
     pub contract_id: Uuid,
     pub initiator_id: Uuid,
     pub reason: String,
+    pub evidence_links: Option<Vec<String>>,
     pub status: String,
+    pub outcome: Option<String>,
+    pub agent_payout_pct: Option<i32>,
     pub resolution: Option<String>,
     pub resolved_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub resolved_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DisputeStatement {
+    pub id: Uuid,
+    pub dispute_id: Uuid,
+    pub author_id: Uuid,
+    pub author_role: String,
+    pub body: String,
+    pub evidence_links: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ═══════════════════════════════════════════════════════════════
 // REQUEST / RESPONSE DTOs
 // ═══════════════════════════════════════════════════════════════
@@ -261,6 +588,38 @@ pub struct CapabilityInput {
     pub proficiency_level: Option<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyReq {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestVerificationReq {
+    pub method: String, // endpoint | wallet
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckVerificationReq {
+    pub wallet: Option<String>,    // wallet method: one of the owner's wallets
+    pub signature: Option<String>, // wallet method: signature over the challenge message
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartBenchmarkReq {
+    pub capability: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBenchmarkTaskReq {
+    pub capability: String,
+    pub name: String,
+    pub input: serde_json::Value,
+    pub expected_output: serde_json::Value,
+    pub scoring: Option<String>, // exact | contains | json_subset | numeric — default exact
+    pub tolerance: Option<f64>,  // numeric only
+    pub weight: Option<f32>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateJobReq {
     pub title: String,
@@ -271,15 +630,76 @@ pub struct CreateJobReq {
     pub battle_mode: Option<bool>,
     pub battle_max_submissions: Option<i32>,
     pub battle_partial_reward_pct: Option<i32>,
+    pub battle_closes_at: Option<DateTime<Utc>>,
     pub deadline: Option<DateTime<Utc>>,
     pub tags: Option<Vec<String>>,
+    pub visibility: Option<String>,       // public | invite_only | private — default public
     pub requirements: Option<Vec<RequirementInput>>,
 }
 
+/// Deadline and battle close are given relative to each job's creation.
 #[derive(Debug, Deserialize)]
+pub struct CreateJobTemplateReq {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub budget_lamports: Option<i64>,
+    pub currency: Option<String>,
+    pub currency_chain: Option<String>,
+    pub battle_mode: Option<bool>,
+    pub battle_max_submissions: Option<i32>,
+    pub battle_partial_reward_pct: Option<i32>,
+    pub battle_duration_hours: Option<i32>,
+    pub deadline_hours: Option<i32>,
+    pub tags: Option<Vec<String>>,
+    pub visibility: Option<String>,
+    pub requirements: Option<Vec<RequirementInput>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateScheduleReq {
+    pub cron: String,
+    pub auto_publish: Option<bool>,
+}
+
+/// PATCH body; omitted fields are left unchanged and nullable fields are cleared
+/// by an explicit `null`. Open jobs only take title, description, budget, deadline and tags.
+#[derive(Debug, Deserialize)]
+pub struct UpdateJobReq {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub budget_lamports: Option<Option<i64>>,
+    pub currency: Option<String>,
+    pub currency_chain: Option<String>,
+    pub battle_mode: Option<bool>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub battle_max_submissions: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub battle_partial_reward_pct: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub battle_closes_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub deadline: Option<Option<DateTime<Utc>>>,
+    pub tags: Option<Vec<String>>,
+    pub visibility: Option<String>,
+}
+
+/// Tell a present `null` (`Some(None)`) apart from a missing field (`None`, via `#[serde(default)]`).
+fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RequirementInput {
     pub requirement: String,
     pub is_mandatory: Option<bool>,
+    pub check_type: Option<String>, // json_schema | regex | max_length | file_types
+    pub check_config: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -294,6 +714,41 @@ pub struct CreateOfferReq {
     pub proposed_price_lamports: Option<i64>,
     pub estimated_duration_hours: Option<i32>,
     pub pitch: Option<String>,
+    pub max_revisions: Option<i32>,
+    pub milestones: Option<Vec<MilestoneInput>>,
+    /// How long the offer stays open; it expires if not accepted by then.
+    pub valid_for_hours: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InviteAgentsReq {
+    pub agent_ids: Vec<Uuid>,
+}
+
+/// Skip bidding: contract a chosen agent at a fixed price.
+#[derive(Debug, Deserialize)]
+pub struct HireAgentReq {
+    pub agent_id: Uuid,
+    pub price_lamports: i64,
+    pub estimated_duration_hours: Option<i32>,
+    pub max_revisions: Option<i32>,
+}
+
+/// Counter-proposal on an offer; omitted fields carry over from the latest version.
+#[derive(Debug, Deserialize)]
+pub struct ProposeRevisionReq {
+    pub price_lamports: Option<i64>,
+    pub estimated_duration_hours: Option<i32>,
+    pub max_revisions: Option<i32>,
+    pub scope: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MilestoneInput {
+    pub title: String,
+    pub amount_lamports: i64,
+    pub due_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -303,11 +758,40 @@ pub struct AcceptOfferReq {
 
 #[derive(Debug, Deserialize)]
 pub struct SubmitWorkReq {
-    pub contract_id: Uuid,
     pub content: String,
     pub artifacts_url: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RejectSubmissionReq {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionReq {
+    pub changes: Vec<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AmendmentReq {
+    pub additional_revisions: i32,
+    pub additional_price_lamports: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunProgressReq {
+    pub message: String,
+    pub progress_pct: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinishRunReq {
+    pub status: String, // completed | failed
+    pub output_summary: Option<String>,
+    pub logs_url: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BattleSubmitReq {
     pub job_id: Uuid,
@@ -322,6 +806,38 @@ pub struct BattleSubmitReq {
 pub struct BattleSelectWinnerReq {
     pub job_id: Uuid,
     pub winner_submission_id: Uuid,
+    /// Runners-up to reward, best first; they share `battle_partial_reward_pct` of the prize
+    pub runner_up_submission_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetRubricReq {
+    /// Omit to seed one criterion per job requirement (mandatory ones weigh double)
+    pub criteria: Option<Vec<RubricCriterionInput>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RubricCriterionInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub weight: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InviteJudgeReq {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScoreSubmissionReq {
+    pub scores: Vec<CriterionScoreInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CriterionScoreInput {
+    pub criterion_id: Uuid,
+    pub score: i32,
+    pub comment: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -357,6 +873,13 @@ pub struct EscrowReleaseReq {
 pub struct DisputeReq {
     pub contract_id: Uuid,
     pub reason: String,
+    pub evidence_links: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisputeStatementReq {
+    pub body: String,
+    pub evidence_links: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -366,6 +889,13 @@ pub struct AdminModerateReviewReq {
     pub weight: Option<f32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AdminResolveDisputeReq {
+    pub outcome: String,              // release | refund | split
+    pub agent_payout_pct: Option<i32>, // required for split (1-99)
+    pub resolution: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AdminSuspendReq {
     pub user_id: Uuid,
@@ -401,6 +931,18 @@ pub struct PaginatedResponse<T: Serialize> {
     pub per_page: i64,
 }
 
+// ─── Notification ───────────────────────────────────────────
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub message: String,
+    pub metadata: Option<serde_json::Value>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ─── Favorite ────────────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Favorite {
@@ -447,18 +989,111 @@ pub struct LeaderboardEntry {
     pub score: f32,
 }
 
+#[derive(Debug, Serialize)]
+pub struct EscrowView {
+    pub accounts: Vec<EscrowAccount>,
+    pub ledger: Vec<EscrowLedgerEntry>,
+}
+
+/// A pending challenge plus what the owner has to do with it.
+#[derive(Debug, Serialize)]
+pub struct VerificationChallengeView {
+    pub verification: AgentVerification,
+    /// endpoint method: serve `token` at this URL
+    pub well_known_url: Option<String>,
+    /// wallet method: sign this exact message
+    pub message: Option<String>,
+}
+
+/// Returned once at creation; `key` is never retrievable again.
+#[derive(Debug, Serialize)]
+pub struct NewApiKeyView {
+    pub api_key: AgentApiKey,
+    pub key: String,
+}
+
+/// Endpoint availability from the health prober; percentages are null without samples.
+#[derive(Debug, Serialize, FromRow)]
+pub struct UptimeStats {
+    pub uptime_24h_pct: Option<f64>,
+    pub uptime_7d_pct: Option<f64>,
+    pub uptime_30d_pct: Option<f64>,
+    pub avg_latency_ms_24h: Option<f64>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub is_up: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DispatchSecretView {
+    pub agent_id: Uuid,
+    pub dispatch_secret: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchmarkRunView {
+    pub run: BenchmarkRun,
+    pub results: Vec<BenchmarkResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobRunView {
+    pub run: JobRun,
+    pub events: Vec<JobRunEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisputeView {
+    pub dispute: Dispute,
+    pub contract: Contract,
+    pub statements: Vec<DisputeStatement>,
+}
+
+/// Outcome of one machine check against a candidate submission.
+#[derive(Debug, Serialize)]
+pub struct RequirementCheckResult {
+    pub requirement_id: Uuid,
+    pub requirement: String,
+    pub is_mandatory: bool,
+    pub check_type: String,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobView {
+    #[serde(flatten)]
+    pub job: Job,
+    /// Diff of the most recent edit, if the job was ever edited
+    pub last_edit: Option<JobRevision>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct BattleView {
     pub job: Job,
+    /// Past `battle_closes_at`, full, or closed by the client
+    pub is_closed: bool,
     pub submissions: Vec<BattleSubmission>,
+    /// Prize contracts once decided, winner first
+    pub prize_contracts: Vec<Contract>,
+    pub rubric: Vec<BattleRubricCriterion>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct BattleSubmission {
     pub submission: Submission,
+    /// Content and artifacts withheld; only `content_hash` is shown
+    pub sealed: bool,
     pub agent: Agent,
     pub proposed_price_lamports: Option<i64>,
     pub estimated_duration_hours: Option<i32>,
+    /// Judges' per-criterion scores; withheld while sealed
+    pub scores: Vec<BattleScore>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BattleRubricView {
+    pub criteria: Vec<BattleRubricCriterion>,
+    pub judges: Vec<BattleJudge>,
 }
 
 
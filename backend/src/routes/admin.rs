@@ -1,8 +1,11 @@
-use axum::{extract::State, Json};
+use axum::{extract::Path, extract::State, Json};
+use uuid::Uuid;
 
 use crate::auth::middleware::AdminUser;
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::services::{benchmarks, escrow};
+use crate::states::{self, ContractStatus, JobState};
 use crate::AppState;
 
 /// POST /api/admin/moderate-review — hide or adjust weight of a review
@@ -98,5 +101,192 @@ pub async fn get_disputes(
     Ok(Json(disputes))
 }
 
+/// Reputation hit for the side that loses a dispute outright; scaled down for splits.
+const DISPUTE_LOSS_PENALTY: f32 = -5.0;
 
+/// POST /api/admin/disputes/:id/resolve — award release, refund or a percentage split
+pub async fn resolve_dispute(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<AdminResolveDisputeReq>,
+) -> AppResult<Json<Dispute>> {
+    if body.resolution.trim().is_empty() {
+        return Err(AppError::BadRequest("Resolution notes are required".into()));
+    }
+
+    // Agent's share of the escrow, in percent
+    let agent_pct = match body.outcome.as_str() {
+        "release" => 100,
+        "refund" => 0,
+        "split" => match body.agent_payout_pct {
+            Some(pct) if (1..=99).contains(&pct) => pct,
+            _ => return Err(AppError::BadRequest("Split requires agent_payout_pct between 1 and 99".into())),
+        },
+        _ => return Err(AppError::BadRequest("outcome must be 'release', 'refund' or 'split'".into())),
+    };
+
+    let mut tx = state.db.begin().await?;
+    let dispute = sqlx::query_as::<_, Dispute>("SELECT * FROM disputes WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Dispute not found".into()))?;
+
+    if !["open", "under_review"].contains(&dispute.status.as_str()) {
+        return Err(AppError::BadRequest("Dispute is already closed".into()));
+    }
+
+    let contract = escrow::lock_contract(&mut tx, dispute.contract_id).await?;
+
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
+        .bind(contract.agent_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    // Move the money
+    let settled = if agent_pct == 0 {
+        escrow::refund_escrow(&mut tx, contract.id).await?
+    } else {
+        escrow::settle_escrow(&mut tx, contract.id, agent_pct).await?
+    };
+    let settled_lamports: i64 = settled.iter().map(|e| e.funded_lamports()).sum();
+
+    let updated = sqlx::query_as::<_, Dispute>(
+        r#"UPDATE disputes
+           SET status = 'resolved', outcome = $1, agent_payout_pct = $2, resolution = $3,
+               resolved_by = $4, resolved_at = now()
+           WHERE id = $5 RETURNING *"#,
+    )
+    .bind(&body.outcome)
+    .bind(agent_pct)
+    .bind(&body.resolution)
+    .bind(claims.sub)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    states::set_contract_status(&mut tx, &contract, ContractStatus::Resolved).await?;
+    states::set_job_state_by_id(&mut tx, contract.job_id, JobState::Resolved).await?;
+
+    // Reputation hit for the losing side (none on an even split)
+    let metadata = serde_json::json!({"dispute_id": id, "outcome": &body.outcome, "agent_payout_pct": agent_pct});
+    if agent_pct < 50 {
+        let delta = DISPUTE_LOSS_PENALTY * (100 - agent_pct) as f32 / 100.0;
+        sqlx::query(
+            r#"INSERT INTO reputation_events (user_id, agent_id, event_type, score_delta, metadata)
+               VALUES ($1, $2, 'dispute_lost', $3, $4)"#,
+        )
+        .bind(agent.owner_id)
+        .bind(agent.id)
+        .bind(delta)
+        .bind(&metadata)
+        .execute(&mut *tx)
+        .await?;
+    } else if agent_pct > 50 {
+        let delta = DISPUTE_LOSS_PENALTY * agent_pct as f32 / 100.0;
+        sqlx::query(
+            r#"INSERT INTO reputation_events (user_id, event_type, score_delta, metadata)
+               VALUES ($1, 'dispute_lost', $2, $3)"#,
+        )
+        .bind(contract.client_id)
+        .bind(delta)
+        .bind(&metadata)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"INSERT INTO audit_logs (user_id, action, entity_type, entity_id, metadata)
+           VALUES ($1, 'resolve_dispute', 'dispute', $2, $3)"#,
+    )
+    .bind(claims.sub)
+    .bind(id)
+    .bind(serde_json::json!({
+        "contract_id": contract.id,
+        "outcome": &body.outcome,
+        "agent_payout_pct": agent_pct,
+        "settled_lamports": settled_lamports,
+    }))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/admin/benchmarks — define an evaluation task for a capability
+pub async fn create_benchmark_task(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Json(body): Json<CreateBenchmarkTaskReq>,
+) -> AppResult<Json<BenchmarkTask>> {
+    let capability = body.capability.trim();
+    if capability.is_empty() || body.name.trim().is_empty() {
+        return Err(AppError::BadRequest("capability and name are required".into()));
+    }
+
+    let scoring = body.scoring.as_deref().unwrap_or("exact");
+    if !benchmarks::SCORING_FUNCTIONS.contains(&scoring) {
+        return Err(AppError::BadRequest(format!(
+            "scoring must be one of: {}", benchmarks::SCORING_FUNCTIONS.join(", ")
+        )));
+    }
+    if scoring == "numeric" && !body.expected_output.is_number() {
+        return Err(AppError::BadRequest("numeric scoring needs a number as expected_output".into()));
+    }
+
+    let weight = body.weight.unwrap_or(1.0);
+    if weight <= 0.0 {
+        return Err(AppError::BadRequest("weight must be positive".into()));
+    }
+
+    let task = sqlx::query_as::<_, BenchmarkTask>(
+        r#"INSERT INTO benchmark_tasks (capability, name, input, expected_output, scoring, tolerance, weight, created_by)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+           RETURNING *"#,
+    )
+    .bind(capability)
+    .bind(body.name.trim())
+    .bind(&body.input)
+    .bind(&body.expected_output)
+    .bind(scoring)
+    .bind(body.tolerance)
+    .bind(weight)
+    .bind(claims.sub)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(task))
+}
+
+/// GET /api/admin/benchmarks — all evaluation tasks, including expected outputs
+pub async fn list_benchmark_tasks(
+    State(state): State<AppState>,
+    AdminUser(_claims): AdminUser,
+) -> AppResult<Json<Vec<BenchmarkTask>>> {
+    let tasks = sqlx::query_as::<_, BenchmarkTask>(
+        "SELECT * FROM benchmark_tasks ORDER BY capability, created_at"
+    )
+    .fetch_all(&state.db)
+    .await?;
 
+    Ok(Json(tasks))
+}
+
+/// POST /api/admin/benchmarks/:id/deactivate — retire a task from future runs
+pub async fn deactivate_benchmark_task(
+    State(state): State<AppState>,
+    AdminUser(_claims): AdminUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<BenchmarkTask>> {
+    let task = sqlx::query_as::<_, BenchmarkTask>(
+        "UPDATE benchmark_tasks SET is_active = false WHERE id = $1 RETURNING *"
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Benchmark task not found".into()))?;
+
+    Ok(Json(task))
+}
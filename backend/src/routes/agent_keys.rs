@@ -0,0 +1,94 @@
+use axum::{extract::Path, extract::State, Json};
+use uuid::Uuid;
+
+use crate::auth::api_keys;
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::agents::owned_agent;
+use crate::AppState;
+
+const MAX_ACTIVE_KEYS: i64 = 10;
+
+/// POST /api/agents/:id/keys — mint an API key for the agent (owner only). The key is only shown once.
+pub async fn create_key(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<CreateApiKeyReq>,
+) -> AppResult<Json<NewApiKeyView>> {
+    let name = body.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::BadRequest("Key name must be 1-100 characters".into()));
+    }
+
+    owned_agent(&state, id, claims.sub).await?;
+
+    let active: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM agent_api_keys WHERE agent_id = $1 AND revoked_at IS NULL"
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await?;
+
+    if active.0 >= MAX_ACTIVE_KEYS {
+        return Err(AppError::BadRequest(format!(
+            "An agent can have at most {MAX_ACTIVE_KEYS} active keys; revoke one first"
+        )));
+    }
+
+    let new_key = api_keys::generate();
+    let api_key = sqlx::query_as::<_, AgentApiKey>(
+        r#"INSERT INTO agent_api_keys (agent_id, name, prefix, key_hash)
+           VALUES ($1, $2, $3, $4)
+           RETURNING *"#,
+    )
+    .bind(id)
+    .bind(name)
+    .bind(&new_key.prefix)
+    .bind(&new_key.hash)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(NewApiKeyView { api_key, key: new_key.key }))
+}
+
+/// GET /api/agents/:id/keys — list the agent's keys, including revoked ones (owner only)
+pub async fn list_keys(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<AgentApiKey>>> {
+    owned_agent(&state, id, claims.sub).await?;
+
+    let keys = sqlx::query_as::<_, AgentApiKey>(
+        "SELECT * FROM agent_api_keys WHERE agent_id = $1 ORDER BY created_at DESC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(keys))
+}
+
+/// POST /api/agents/:id/keys/:key_id/revoke — revoke a key immediately (owner only)
+pub async fn revoke_key(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((id, key_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<AgentApiKey>> {
+    owned_agent(&state, id, claims.sub).await?;
+
+    let revoked = sqlx::query_as::<_, AgentApiKey>(
+        r#"UPDATE agent_api_keys SET revoked_at = now()
+           WHERE id = $1 AND agent_id = $2 AND revoked_at IS NULL
+           RETURNING *"#,
+    )
+    .bind(key_id)
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Key not found or already revoked".into()))?;
+
+    Ok(Json(revoked))
+}
@@ -5,6 +5,7 @@ use uuid::Uuid;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::services::{dispatch, health};
 use crate::AppState;
 
 /// POST /api/agents — register a new agent
@@ -192,6 +193,40 @@ pub async fn activate_agent(
     Ok(Json(updated))
 }
 
+/// GET /api/agents/:id/dispatch-secret — HMAC key for verifying job dispatches (owner only)
+pub async fn get_dispatch_secret(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<DispatchSecretView>> {
+    owned_agent(&state, id, claims.sub).await?;
+    let dispatch_secret = dispatch::agent_secret(&state.db, id).await?;
+
+    Ok(Json(DispatchSecretView { agent_id: id, dispatch_secret }))
+}
+
+/// POST /api/agents/:id/dispatch-secret/rotate — replace the dispatch HMAC key (owner only)
+pub async fn rotate_dispatch_secret(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<DispatchSecretView>> {
+    owned_agent(&state, id, claims.sub).await?;
+    let dispatch_secret = dispatch::rotate_secret(&state.db, id).await?;
+
+    Ok(Json(DispatchSecretView { agent_id: id, dispatch_secret }))
+}
+
+/// An agent owned by `user_id`; NotFound otherwise.
+pub async fn owned_agent(state: &AppState, id: Uuid, user_id: Uuid) -> AppResult<Agent> {
+    sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1 AND owner_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Agent not found or not yours".into()))
+}
+
 // ═══════════════════════════════════════════════════════════════
 // AGENT PROFILE — full profile with capabilities, work history, reviews
 // ═══════════════════════════════════════════════════════════════
@@ -203,6 +238,11 @@ pub struct AgentProfile {
     pub owner_name: Option<String>,
     pub review_stats: ReviewStats,
     pub completed_jobs: Vec<CompletedJob>,
+    pub uptime: UptimeStats,
+    /// Latest completed benchmark run per capability
+    pub benchmarks: Vec<BenchmarkRun>,
+    /// Battle rating changes, newest first (the rating itself is on `agent`)
+    pub battle_history: Vec<BattleRatingChange>,
 }
 
 #[derive(Debug, Serialize)]
@@ -306,11 +346,12 @@ pub async fn get_agent_profile(
         },
     };
 
-    // Completed jobs (last 20)
+    // Completed public jobs (last 20)
     let contracts = sqlx::query_as::<_, Contract>(
-        r#"SELECT * FROM contracts
-           WHERE agent_id = $1 AND status = 'completed'
-           ORDER BY completed_at DESC LIMIT 20"#,
+        r#"SELECT c.* FROM contracts c
+           JOIN jobs j ON j.id = c.job_id
+           WHERE c.agent_id = $1 AND c.status = 'completed' AND j.visibility = 'public'
+           ORDER BY c.completed_at DESC LIMIT 20"#,
     )
     .bind(id)
     .fetch_all(&state.db)
@@ -349,11 +390,38 @@ pub async fn get_agent_profile(
         }
     }
 
+    // Endpoint availability
+    let uptime = health::uptime(&state.db, id).await?;
+
+    // Benchmark results
+    let benchmarks = sqlx::query_as::<_, BenchmarkRun>(
+        r#"SELECT DISTINCT ON (capability) * FROM benchmark_runs
+           WHERE agent_id = $1 AND status = 'completed'
+           ORDER BY capability, finished_at DESC"#,
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    // Battle rating history (public battles only)
+    let battle_history = sqlx::query_as::<_, BattleRatingChange>(
+        r#"SELECT h.* FROM battle_rating_history h
+           JOIN jobs j ON j.id = h.job_id
+           WHERE h.agent_id = $1 AND j.visibility = 'public'
+           ORDER BY h.created_at DESC LIMIT 20"#,
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
     Ok(Json(AgentProfile {
         agent,
         capabilities,
         owner_name,
         review_stats,
         completed_jobs,
+        uptime,
+        benchmarks,
+        battle_history,
     }))
 }
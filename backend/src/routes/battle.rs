@@ -1,47 +1,60 @@
 use axum::{extract::Path, extract::State, Json};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use sqlx::PgConnection;
 use uuid::Uuid;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::{AgentAuth, AuthUser, OptionalAuth};
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::routes::{invites, judging};
+use crate::services::{battle_rating, escrow, requirement_checks};
+use crate::states::{self, JobState, OfferStatus, StateMachine, SubmissionStatus};
 use crate::AppState;
 
 /// POST /api/battle/submit — submit to a battle mode job
 pub async fn battle_submit(
     State(state): State<AppState>,
-    AuthUser(claims): AuthUser,
+    AgentAuth(caller): AgentAuth,
     Json(body): Json<BattleSubmitReq>,
 ) -> AppResult<Json<Submission>> {
+    caller.check_agent(body.agent_id)?;
+
     // Verify agent belongs to user
     let _agent = sqlx::query_as::<_, Agent>(
         "SELECT * FROM agents WHERE id = $1 AND owner_id = $2"
     )
     .bind(body.agent_id)
-    .bind(claims.sub)
+    .bind(caller.user_id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| AppError::Forbidden("Agent does not belong to you".into()))?;
 
-    // Verify job is open + battle mode
-    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+    // Verify job is open + battle mode; the row lock keeps the submission cap exact
+    let mut tx = state.db.begin().await?;
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1 FOR UPDATE")
         .bind(body.job_id)
-        .fetch_optional(&state.db)
+        .fetch_optional(&mut *tx)
         .await?
         .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
 
     if !job.battle_mode {
         return Err(AppError::BadRequest("This job is not in battle mode".into()));
     }
-    if job.state != "open" {
+    if job.state != JobState::Open {
         return Err(AppError::BadRequest("Job is not accepting submissions".into()));
     }
+    if let Some(closes_at) = job.battle_closes_at.filter(|t| *t <= Utc::now()) {
+        return Err(AppError::BadRequest(format!("Battle closed for submissions at {closes_at}")));
+    }
+    invites::check_can_bid(&state.db, &job, body.agent_id).await?;
 
     // Check max submissions
     let count: (i64,) = sqlx::query_as(
         "SELECT COUNT(*) FROM submissions WHERE job_id = $1 AND is_battle_submission = true"
     )
     .bind(body.job_id)
-    .fetch_one(&state.db)
+    .fetch_one(&mut *tx)
     .await?;
 
     // NULL or 0 = no limit
@@ -59,25 +72,30 @@ pub async fn battle_submit(
     )
     .bind(body.job_id)
     .bind(body.agent_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await?;
 
     if existing.is_some() {
         return Err(AppError::Conflict("Agent already submitted to this battle".into()));
     }
 
+    let checks = requirement_checks::check_submission(&mut tx, job.id, &body.content, body.artifacts_url.as_deref()).await?;
+
     let submission = sqlx::query_as::<_, Submission>(
-        r#"INSERT INTO submissions (job_id, agent_id, content, artifacts_url, is_battle_submission, status)
-           VALUES ($1, $2, $3, $4, true, 'pending')
+        r#"INSERT INTO submissions (job_id, agent_id, content, artifacts_url, is_battle_submission, content_hash, status)
+           VALUES ($1, $2, $3, $4, true, $5, 'pending')
            RETURNING *"#,
     )
     .bind(body.job_id)
     .bind(body.agent_id)
     .bind(&body.content)
     .bind(&body.artifacts_url)
-    .fetch_one(&state.db)
+    .bind(hex::encode(Sha256::digest(body.content.as_bytes())))
+    .fetch_one(&mut *tx)
     .await?;
 
+    requirement_checks::record(&mut tx, submission.id, &checks).await?;
+
     // Also create an offer record for the battle
     sqlx::query(
         r#"INSERT INTO offers (job_id, agent_id, proposed_price_lamports, estimated_duration_hours, pitch, status)
@@ -87,15 +105,24 @@ pub async fn battle_submit(
     .bind(body.agent_id)
     .bind(body.proposed_price_lamports)
     .bind(body.estimated_duration_hours)
-    .execute(&state.db)
+    .execute(&mut *tx)
     .await?;
 
+    // Field is full: close it for judging (open → reviewing)
+    if matches!(job.battle_max_submissions, Some(max) if max > 0 && count.0 + 1 >= max as i64) {
+        states::set_job_state(&mut tx, &job, JobState::Reviewing).await?;
+    }
+    tx.commit().await?;
+
     Ok(Json(submission))
 }
 
-/// GET /api/battle/:job_id — get battle view for a job
+/// GET /api/battle/:job_id — get battle view for a job.
+/// Entries stay sealed until the battle closes (then the judges see them) and
+/// the winner is picked (then everyone does); agents always see their own.
 pub async fn get_battle(
     State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
     Path(job_id): Path<Uuid>,
 ) -> AppResult<Json<BattleView>> {
     let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
@@ -104,6 +131,10 @@ pub async fn get_battle(
         .await?
         .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
 
+    let viewer = claims.map(|c| c.sub);
+    if !invites::can_view(&state.db, &job, viewer).await? {
+        return Err(AppError::NotFound("Job not found".into()));
+    }
     if !job.battle_mode {
         return Err(AppError::BadRequest("Not a battle mode job".into()));
     }
@@ -115,13 +146,39 @@ pub async fn get_battle(
     .fetch_all(&state.db)
     .await?;
 
+    let is_closed = is_closed(&job);
+    let is_judge = match viewer {
+        Some(user_id) if is_closed => judging::is_judge(&mut *state.db.acquire().await?, &job, user_id).await?,
+        _ => false,
+    };
+    let revealed = job.state == JobState::Completed || is_judge;
+
     let mut battle_subs = Vec::new();
-    for sub in submissions {
+    for mut sub in submissions {
         let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
             .bind(sub.agent_id)
             .fetch_one(&state.db)
             .await?;
 
+        let sealed = !revealed && viewer != Some(agent.owner_id);
+        if sealed {
+            sub.content = String::new();
+            sub.artifacts_url = None;
+            sub.rubric_score = None;
+            sub.rubric_rank = None;
+        }
+
+        let scores = if sealed {
+            Vec::new()
+        } else {
+            sqlx::query_as::<_, BattleScore>(
+                "SELECT * FROM battle_scores WHERE submission_id = $1 ORDER BY created_at"
+            )
+            .bind(sub.id)
+            .fetch_all(&state.db)
+            .await?
+        };
+
         let offer = sqlx::query_as::<_, Offer>(
             "SELECT * FROM offers WHERE job_id = $1 AND agent_id = $2 ORDER BY created_at DESC LIMIT 1"
         )
@@ -132,98 +189,285 @@ pub async fn get_battle(
 
         battle_subs.push(BattleSubmission {
             submission: sub,
+            sealed,
             agent,
             proposed_price_lamports: offer.as_ref().and_then(|o| o.proposed_price_lamports),
             estimated_duration_hours: offer.as_ref().and_then(|o| o.estimated_duration_hours),
+            scores,
         });
     }
 
+    let prize_contracts = sqlx::query_as::<_, Contract>(
+        "SELECT * FROM contracts WHERE job_id = $1 AND battle_rank IS NOT NULL AND status <> 'cancelled' ORDER BY battle_rank"
+    )
+    .bind(job_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let rubric = judging::rubric_criteria(&state.db, job_id).await?;
+
     Ok(Json(BattleView {
         job,
+        is_closed,
         submissions: battle_subs,
+        prize_contracts,
+        rubric,
     }))
 }
 
-/// POST /api/battle/select-winner — select a battle winner
+/// POST /api/battle/:job_id/close — stop taking submissions early (open → reviewing)
+pub async fn close_battle(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<Job>> {
+    let mut tx = state.db.begin().await?;
+    let job = sqlx::query_as::<_, Job>(
+        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE"
+    )
+    .bind(job_id)
+    .bind(claims.sub)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Forbidden("Not your job".into()))?;
+
+    if !job.battle_mode {
+        return Err(AppError::BadRequest("Not a battle mode job".into()));
+    }
+
+    let updated = states::set_job_state(&mut tx, &job, JobState::Reviewing).await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/battle/select-winner — select a battle winner and any rewarded runners-up
 pub async fn select_winner(
     State(state): State<AppState>,
     AuthUser(claims): AuthUser,
     Json(body): Json<BattleSelectWinnerReq>,
 ) -> AppResult<Json<Contract>> {
+    let runner_up_ids = body.runner_up_submission_ids.clone().unwrap_or_default();
+    if runner_up_ids.contains(&body.winner_submission_id) {
+        return Err(AppError::BadRequest("The winner cannot also be a runner-up".into()));
+    }
+    if (1..runner_up_ids.len()).any(|i| runner_up_ids[..i].contains(&runner_up_ids[i])) {
+        return Err(AppError::BadRequest("Runner-up submissions must be distinct".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
     let job = sqlx::query_as::<_, Job>(
-        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2"
+        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE"
     )
     .bind(body.job_id)
     .bind(claims.sub)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await?
     .ok_or_else(|| AppError::Forbidden("Not your job".into()))?;
 
     if !job.battle_mode {
         return Err(AppError::BadRequest("Not a battle mode job".into()));
     }
+    // Fail before touching submissions if the battle is already decided
+    job.state.transition(JobState::Completed)?;
+    // The client judges revealed entries only
+    if !is_closed(&job) {
+        return Err(AppError::BadRequest("Close the battle before selecting a winner".into()));
+    }
+
+    // The prize pool is the job budget, whatever the entrants bid
+    let prize = job
+        .budget_lamports
+        .ok_or_else(|| AppError::BadRequest("Set a budget on the battle before selecting a winner".into()))?;
+    let reward_pct = job.battle_partial_reward_pct.unwrap_or(0);
+    if !runner_up_ids.is_empty() && reward_pct <= 0 {
+        return Err(AppError::BadRequest("This battle has no runner-up reward".into()));
+    }
 
     let submission = sqlx::query_as::<_, Submission>(
-        "SELECT * FROM submissions WHERE id = $1 AND job_id = $2"
+        "SELECT * FROM submissions WHERE id = $1 AND job_id = $2 AND is_battle_submission = true FOR UPDATE"
     )
     .bind(body.winner_submission_id)
     .bind(body.job_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await?
     .ok_or_else(|| AppError::NotFound("Submission not found".into()))?;
 
-    // Accept the winner submission
-    sqlx::query("UPDATE submissions SET status = 'accepted' WHERE id = $1")
-        .bind(body.winner_submission_id)
-        .execute(&state.db)
-        .await?;
+    let mut runners_up = Vec::with_capacity(runner_up_ids.len());
+    for id in &runner_up_ids {
+        let runner_up = sqlx::query_as::<_, Submission>(
+            "SELECT * FROM submissions WHERE id = $1 AND job_id = $2 AND is_battle_submission = true FOR UPDATE"
+        )
+        .bind(id)
+        .bind(body.job_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Runner-up submission {id} not found")))?;
+        runners_up.push(runner_up);
+    }
+
+    // Accept the winner and the rewarded runners-up
+    states::set_submission_status(&mut tx, &submission, SubmissionStatus::Accepted, None).await?;
+    for runner_up in &runners_up {
+        states::set_submission_status(&mut tx, runner_up, SubmissionStatus::Accepted, None).await?;
+    }
 
     // Reject others
-    sqlx::query("UPDATE submissions SET status = 'rejected' WHERE job_id = $1 AND id != $2 AND is_battle_submission = true")
-        .bind(body.job_id)
-        .bind(body.winner_submission_id)
-        .execute(&state.db)
-        .await?;
+    let others = sqlx::query_as::<_, Submission>(
+        "SELECT * FROM submissions WHERE job_id = $1 AND is_battle_submission = true AND status = 'pending' FOR UPDATE"
+    )
+    .bind(body.job_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    for other in &others {
+        states::set_submission_status(&mut tx, other, SubmissionStatus::Rejected, None).await?;
+    }
+
+    let offer = latest_offer(&mut tx, body.job_id, submission.agent_id).await?;
+    let (winner_amount, shares) = prize_shares(prize, reward_pct, runners_up.len());
+    if shares.contains(&0) {
+        return Err(AppError::BadRequest(format!(
+            "A prize of {prize} lamports is too small to reward {} runner(s)-up", runners_up.len()
+        )));
+    }
 
-    // Find corresponding offer
-    let offer = sqlx::query_as::<_, Offer>(
-        "SELECT * FROM offers WHERE job_id = $1 AND agent_id = $2 ORDER BY created_at DESC LIMIT 1"
+    let contract = award_prize(&mut tx, &job, &offer, 1, winner_amount).await?;
+    let mut prize_offers = vec![offer];
+    for (i, (runner_up, amount)) in runners_up.iter().zip(shares).enumerate() {
+        let offer = latest_offer(&mut tx, body.job_id, runner_up.agent_id).await?;
+        award_prize(&mut tx, &job, &offer, i as i32 + 2, amount).await?;
+        prize_offers.push(offer);
+    }
+
+    // Offers behind a prize contract are accepted, every other entrant's is rejected
+    for offer in prize_offers.iter().filter(|o| o.status == OfferStatus::Pending) {
+        states::set_offer_status(&mut tx, offer, OfferStatus::Accepted).await?;
+    }
+    let losing_offers = sqlx::query_as::<_, Offer>(
+        "SELECT * FROM offers WHERE job_id = $1 AND status = 'pending' FOR UPDATE"
     )
     .bind(body.job_id)
-    .bind(submission.agent_id)
-    .fetch_one(&state.db)
+    .fetch_all(&mut *tx)
     .await?;
+    for offer in &losing_offers {
+        states::set_offer_status(&mut tx, offer, OfferStatus::Rejected).await?;
+    }
 
-    let price = offer.proposed_price_lamports.unwrap_or(0);
+    // Re-rate every entrant: winner first, then the rewarded runners-up
+    let placed: Vec<Uuid> = std::iter::once(body.winner_submission_id).chain(runner_up_ids.iter().copied()).collect();
+    battle_rating::rate_battle(&mut tx, body.job_id, &placed).await?;
 
-    // Create contract
-    let contract = sqlx::query_as::<_, Contract>(
-        r#"INSERT INTO contracts (job_id, offer_id, agent_id, client_id, agreed_price_lamports)
-           VALUES ($1, $2, $3, $4, $5) RETURNING *"#,
+    // Record the decision next to the rubric ranking so the outcome is auditable
+    let ranking: Vec<(Uuid, Option<f32>, Option<i32>)> = sqlx::query_as(
+        r#"SELECT id, rubric_score, rubric_rank FROM submissions
+           WHERE job_id = $1 AND is_battle_submission = true
+           ORDER BY rubric_rank NULLS LAST, created_at"#,
     )
     .bind(body.job_id)
-    .bind(offer.id)
-    .bind(submission.agent_id)
-    .bind(claims.sub)
-    .bind(price)
-    .fetch_one(&state.db)
+    .fetch_all(&mut *tx)
     .await?;
 
-    // Create escrow
-    sqlx::query("INSERT INTO escrow_accounts (contract_id, amount_lamports) VALUES ($1, $2)")
-        .bind(contract.id)
-        .bind(price)
-        .execute(&state.db)
-        .await?;
+    sqlx::query(
+        r#"INSERT INTO audit_logs (user_id, action, entity_type, entity_id, metadata)
+           VALUES ($1, 'select_battle_winner', 'job', $2, $3)"#,
+    )
+    .bind(claims.sub)
+    .bind(body.job_id)
+    .bind(serde_json::json!({
+        "winner_submission_id": body.winner_submission_id,
+        "runner_up_submission_ids": runner_up_ids,
+        "ranking": ranking
+            .iter()
+            .map(|(id, score, rank)| serde_json::json!({"submission_id": id, "rubric_score": score, "rubric_rank": rank}))
+            .collect::<Vec<_>>(),
+    }))
+    .execute(&mut *tx)
+    .await?;
 
     // Update job state
-    sqlx::query("UPDATE jobs SET state = 'completed', updated_at = now() WHERE id = $1")
-        .bind(body.job_id)
-        .execute(&state.db)
-        .await?;
+    states::set_job_state(&mut tx, &job, JobState::Completed).await?;
+    tx.commit().await?;
 
     Ok(Json(contract))
 }
 
+/// A battle stops taking (and hiding from the client) submissions once it leaves
+/// `open` — field full or closed by the client — or its close time passes.
+pub fn is_closed(job: &Job) -> bool {
+    job.state != JobState::Open || job.battle_closes_at.is_some_and(|t| t <= Utc::now())
+}
+
+async fn latest_offer(conn: &mut PgConnection, job_id: Uuid, agent_id: Uuid) -> AppResult<Offer> {
+    Ok(sqlx::query_as::<_, Offer>(
+        "SELECT * FROM offers WHERE job_id = $1 AND agent_id = $2 ORDER BY created_at DESC LIMIT 1 FOR UPDATE"
+    )
+    .bind(job_id)
+    .bind(agent_id)
+    .fetch_one(&mut *conn)
+    .await?)
+}
+
+/// Split `prize` (the job budget) into the winner's amount and one share per runner-up.
+/// Runners-up divide `reward_pct` percent of the budget by rank: with n of them the
+/// best gets n parts, the next n - 1, down to 1; rounding dust goes to the best.
+fn prize_shares(prize: i64, reward_pct: i32, runners_up: usize) -> (i64, Vec<i64>) {
+    if runners_up == 0 {
+        return (prize, Vec::new());
+    }
+
+    let pool = prize * reward_pct as i64 / 100;
+    let n = runners_up as i64;
+    let parts = n * (n + 1) / 2;
+    let mut shares: Vec<i64> = (0..n).map(|i| pool * (n - i) / parts).collect();
+    shares[0] += pool - shares.iter().sum::<i64>();
+
+    (prize - pool, shares)
+}
+
+/// Create a prize contract and its escrow. The work is already delivered, so the
+/// contract pays out as soon as the client funds it.
+async fn award_prize(conn: &mut PgConnection, job: &Job, offer: &Offer, rank: i32, amount: i64) -> AppResult<Contract> {
+    let contract = sqlx::query_as::<_, Contract>(
+        r#"INSERT INTO contracts (job_id, offer_id, agent_id, client_id, agreed_price_lamports, max_revisions,
+                                  battle_rank, delivered_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, now()) RETURNING *"#,
+    )
+    .bind(job.id)
+    .bind(offer.id)
+    .bind(offer.agent_id)
+    .bind(job.client_id)
+    .bind(amount)
+    .bind(offer.max_revisions)
+    .bind(rank)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    escrow::open_escrow(conn, &contract, &[]).await?;
 
+    Ok(contract)
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prize_shares_split_by_rank() {
+        // (budget, reward_pct, runners_up) → (winner, runner-up shares)
+        let cases: [(i64, i32, usize, i64, &[i64]); 7] = [
+            (1000, 30, 0, 1000, &[]),
+            (1000, 0, 2, 1000, &[0, 0]),
+            (1000, 30, 1, 700, &[300]),
+            (1000, 30, 3, 700, &[150, 100, 50]),
+            (1000, 99, 4, 10, &[396, 297, 198, 99]),
+            // pool 992 over 6 parts leaves 1 lamport of dust for the best runner-up
+            (1003, 99, 3, 11, &[497, 330, 165]),
+            (7, 50, 2, 4, &[2, 1]),
+        ];
+        for (prize, pct, runners_up, winner, shares) in cases {
+            let (got_winner, got_shares) = prize_shares(prize, pct, runners_up);
+            assert_eq!((got_winner, got_shares.as_slice()), (winner, shares), "{prize} at {pct}% over {runners_up}");
+            assert_eq!(got_winner + got_shares.iter().sum::<i64>(), prize, "nothing lost to rounding");
+        }
+    }
+}
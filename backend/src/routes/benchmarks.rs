@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use axum::{extract::Path, extract::State, Json};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::agents::owned_agent;
+use crate::services::{benchmarks, outbound};
+use crate::AppState;
+
+/// Minimum gap between runs of the same capability, so tasks can't be brute-forced.
+const RUN_COOLDOWN_HOURS: i32 = 24;
+
+/// POST /api/agents/:id/benchmarks — run the capability's evaluation tasks against the agent's endpoint (owner only).
+/// Runs in the background; poll the run for results.
+pub async fn start_benchmark(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<StartBenchmarkReq>,
+) -> AppResult<Json<BenchmarkRun>> {
+    let agent = owned_agent(&state, id, claims.sub).await?;
+
+    let endpoint_url = agent
+        .endpoint_url
+        .filter(|u| !u.trim().is_empty())
+        .ok_or_else(|| AppError::BadRequest("Agent has no endpoint_url to benchmark".into()))?;
+    outbound::check_url(&endpoint_url).await.map_err(AppError::BadRequest)?;
+
+    let declared: (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM agent_capabilities WHERE agent_id = $1 AND capability = $2)"
+    )
+    .bind(id)
+    .bind(&body.capability)
+    .fetch_one(&state.db)
+    .await?;
+
+    if !declared.0 {
+        return Err(AppError::BadRequest("Agent does not declare this capability".into()));
+    }
+
+    let tasks: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM benchmark_tasks WHERE capability = $1 AND is_active"
+    )
+    .bind(&body.capability)
+    .fetch_one(&state.db)
+    .await?;
+
+    if tasks.0 == 0 {
+        return Err(AppError::BadRequest("No benchmark exists for this capability yet".into()));
+    }
+
+    let recent: (bool,) = sqlx::query_as(
+        r#"SELECT EXISTS (
+               SELECT 1 FROM benchmark_runs
+               WHERE agent_id = $1 AND capability = $2 AND status = 'completed'
+                 AND finished_at > now() - make_interval(hours => $3)
+           )"#,
+    )
+    .bind(id)
+    .bind(&body.capability)
+    .bind(RUN_COOLDOWN_HOURS)
+    .fetch_one(&state.db)
+    .await?;
+
+    if recent.0 {
+        return Err(AppError::BadRequest(format!(
+            "This capability was benchmarked in the last {RUN_COOLDOWN_HOURS} hours"
+        )));
+    }
+
+    // idx_benchmark_runs_running allows one run per agent + capability at a time
+    let run = sqlx::query_as::<_, BenchmarkRun>(
+        r#"INSERT INTO benchmark_runs (agent_id, capability)
+           VALUES ($1, $2)
+           ON CONFLICT (agent_id, capability) WHERE status = 'running' DO NOTHING
+           RETURNING *"#,
+    )
+    .bind(id)
+    .bind(&body.capability)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::Conflict("A benchmark for this capability is already running".into()))?;
+
+    let http = outbound::client(Duration::from_secs(60)).map_err(|e| AppError::Internal(e.to_string()))?;
+    tokio::spawn(benchmarks::execute(state.db.clone(), http, run.clone(), endpoint_url));
+
+    Ok(Json(run))
+}
+
+/// GET /api/agents/:id/benchmarks — benchmark runs with scores, newest first
+pub async fn list_benchmarks(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<BenchmarkRun>>> {
+    let runs = sqlx::query_as::<_, BenchmarkRun>(
+        "SELECT * FROM benchmark_runs WHERE agent_id = $1 ORDER BY started_at DESC LIMIT 50"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(runs))
+}
+
+/// GET /api/benchmark-runs/:id — a run with per-task results (owner only)
+pub async fn get_benchmark_run(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<BenchmarkRunView>> {
+    let run = sqlx::query_as::<_, BenchmarkRun>("SELECT * FROM benchmark_runs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Benchmark run not found".into()))?;
+
+    owned_agent(&state, run.agent_id, claims.sub).await?;
+
+    let results = sqlx::query_as::<_, BenchmarkResult>(
+        "SELECT * FROM benchmark_results WHERE run_id = $1 ORDER BY created_at"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(BenchmarkRunView { run, results }))
+}
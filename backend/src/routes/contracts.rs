@@ -0,0 +1,571 @@
+use axum::{extract::Path, extract::State, Json};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::middleware::{AgentAuth, AuthUser};
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::milestones;
+use crate::services::{escrow, requirement_checks};
+use crate::states::{self, ContractStatus, JobState, SubmissionStatus};
+use crate::AppState;
+
+/// GET /api/contracts/:id — contract details (client or agent owner)
+pub async fn get_contract(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Contract>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    Ok(Json(contract))
+}
+
+/// GET /api/contracts/:id/submissions — all submissions on a contract, newest first
+pub async fn list_contract_submissions(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<Submission>>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let submissions = sqlx::query_as::<_, Submission>(
+        "SELECT * FROM submissions WHERE contract_id = $1 ORDER BY created_at DESC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(submissions))
+}
+
+/// GET /api/submissions/:id/checks — machine-check results recorded for a submission
+/// (job owner or the submitting agent's owner)
+pub async fn list_submission_checks(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<SubmissionCheck>>> {
+    let allowed: Option<(bool,)> = sqlx::query_as(
+        r#"SELECT j.client_id = $2 OR a.owner_id = $2
+           FROM submissions s
+           JOIN jobs j ON j.id = s.job_id
+           JOIN agents a ON a.id = s.agent_id
+           WHERE s.id = $1"#,
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match allowed {
+        None => return Err(AppError::NotFound("Submission not found".into())),
+        Some((false,)) => return Err(AppError::Forbidden("Not your submission".into())),
+        Some((true,)) => {}
+    }
+
+    let checks = sqlx::query_as::<_, SubmissionCheck>(
+        "SELECT * FROM submission_checks WHERE submission_id = $1 ORDER BY created_at"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(checks))
+}
+
+/// GET /api/contracts/:id/escrow — escrow accounts (one per milestone tranche) and their ledger
+pub async fn get_contract_escrow(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<EscrowView>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let accounts = sqlx::query_as::<_, EscrowAccount>(
+        "SELECT * FROM escrow_accounts WHERE contract_id = $1 ORDER BY created_at ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let ledger = sqlx::query_as::<_, EscrowLedgerEntry>(
+        r#"SELECT l.* FROM escrow_ledger_entries l
+           JOIN escrow_accounts e ON l.escrow_id = e.id
+           WHERE e.contract_id = $1
+           ORDER BY l.created_at ASC"#,
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(EscrowView { accounts, ledger }))
+}
+
+/// POST /api/contracts/:id/submit — agent owner submits work (in_progress → submitted, escrow locked)
+pub async fn submit_work(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SubmitWorkReq>,
+) -> AppResult<Json<Submission>> {
+    if body.content.trim().is_empty() {
+        return Err(AppError::BadRequest("Submission content is required".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let contract = escrow::lock_contract(&mut tx, id).await?;
+    caller.check_agent(contract.agent_id)?;
+
+    // Verify agent belongs to user
+    let _agent = sqlx::query_as::<_, Agent>(
+        "SELECT * FROM agents WHERE id = $1 AND owner_id = $2"
+    )
+    .bind(contract.agent_id)
+    .bind(caller.user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Forbidden("Only the contracted agent's owner can submit work".into()))?;
+
+    let submission = deliver_work(&mut tx, &contract, &body).await?;
+    tx.commit().await?;
+
+    Ok(Json(submission))
+}
+
+/// POST /api/contracts/:id/approve — client accepts the latest submission and releases escrow
+pub async fn approve_submission(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Submission>> {
+    let mut tx = state.db.begin().await?;
+    let (contract, submission) = pending_submission_for_client(&mut tx, id, claims.sub).await?;
+
+    let updated = states::set_submission_status(&mut tx, &submission, SubmissionStatus::Accepted, None).await?;
+
+    // locked → released; also completes the contract + job
+    escrow::release_escrow(&mut tx, contract.id, claims.sub).await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/contracts/:id/reject — client rejects the latest submission (submitted → in_progress).
+/// Counts as a revision round.
+pub async fn reject_submission(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<RejectSubmissionReq>,
+) -> AppResult<Json<Submission>> {
+    if body.reason.trim().is_empty() {
+        return Err(AppError::BadRequest("A rejection reason is required".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let (contract, submission) = pending_submission_for_client(&mut tx, id, claims.sub).await?;
+    ensure_revision_available(&mut tx, &contract).await?;
+
+    let updated = states::set_submission_status(
+        &mut tx,
+        &submission,
+        SubmissionStatus::Rejected,
+        Some(&body.reason),
+    )
+    .await?;
+
+    reopen_for_rework(&mut tx, &contract).await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/contracts/:id/request-revision — client asks for specific changes (submitted → in_progress)
+pub async fn request_revision(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<RevisionReq>,
+) -> AppResult<Json<RevisionRequest>> {
+    let changes: Vec<String> = body
+        .changes
+        .iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if changes.is_empty() {
+        return Err(AppError::BadRequest("List at least one requested change".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let (contract, submission) = pending_submission_for_client(&mut tx, id, claims.sub).await?;
+    let used = ensure_revision_available(&mut tx, &contract).await?;
+
+    states::set_submission_status(
+        &mut tx,
+        &submission,
+        SubmissionStatus::RevisionRequested,
+        body.message.as_deref(),
+    )
+    .await?;
+
+    let revision = sqlx::query_as::<_, RevisionRequest>(
+        r#"INSERT INTO revision_requests (contract_id, submission_id, requested_by, round, changes, message)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           RETURNING *"#,
+    )
+    .bind(contract.id)
+    .bind(submission.id)
+    .bind(claims.sub)
+    .bind(used as i32 + 1)
+    .bind(&changes)
+    .bind(&body.message)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    reopen_for_rework(&mut tx, &contract).await?;
+    tx.commit().await?;
+
+    Ok(Json(revision))
+}
+
+/// GET /api/contracts/:id/revisions — revision requests on a contract, oldest first
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<RevisionRequest>>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let revisions = sqlx::query_as::<_, RevisionRequest>(
+        "SELECT * FROM revision_requests WHERE contract_id = $1 ORDER BY round ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(revisions))
+}
+
+/// POST /api/contracts/:id/amendments — client offers extra revision rounds and/or more money
+pub async fn propose_amendment(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<AmendmentReq>,
+) -> AppResult<Json<ContractAmendment>> {
+    let extra_price = body.additional_price_lamports.unwrap_or(0);
+    if body.additional_revisions < 0 || extra_price < 0 {
+        return Err(AppError::BadRequest("Amendments can only add revisions or budget".into()));
+    }
+    if body.additional_revisions == 0 && extra_price == 0 {
+        return Err(AppError::BadRequest("Amendment changes nothing".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let contract = escrow::lock_contract(&mut tx, id).await?;
+
+    if contract.client_id != claims.sub {
+        return Err(AppError::Forbidden("Not your contract".into()));
+    }
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest("Contract is not active".into()));
+    }
+    if extra_price > 0 && milestones::is_milestone_contract(&mut tx, contract.id).await? {
+        return Err(AppError::BadRequest("Milestone contracts cannot add budget through an amendment".into()));
+    }
+
+    let pending = sqlx::query_as::<_, ContractAmendment>(
+        "SELECT * FROM contract_amendments WHERE contract_id = $1 AND status = 'pending'"
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if pending.is_some() {
+        return Err(AppError::Conflict("An amendment is already awaiting the agent's answer".into()));
+    }
+
+    let amendment = sqlx::query_as::<_, ContractAmendment>(
+        r#"INSERT INTO contract_amendments (contract_id, proposed_by, additional_revisions, additional_price_lamports)
+           VALUES ($1, $2, $3, $4)
+           RETURNING *"#,
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .bind(body.additional_revisions)
+    .bind(extra_price)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(amendment))
+}
+
+/// GET /api/contracts/:id/amendments — amendment history
+pub async fn list_amendments(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<ContractAmendment>>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let amendments = sqlx::query_as::<_, ContractAmendment>(
+        "SELECT * FROM contract_amendments WHERE contract_id = $1 ORDER BY created_at ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(amendments))
+}
+
+/// POST /api/contracts/:id/amendments/:amendment_id/accept — agent owner agrees; terms + escrow grow
+pub async fn accept_amendment(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((id, amendment_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Contract>> {
+    let mut tx = state.db.begin().await?;
+    let (contract, amendment) = pending_amendment_for_agent(&mut tx, id, amendment_id, claims.sub).await?;
+
+    if amendment.additional_price_lamports > 0 {
+        escrow::top_up_escrow(&mut tx, contract.id, amendment.additional_price_lamports).await?;
+    }
+
+    let updated = sqlx::query_as::<_, Contract>(
+        r#"UPDATE contracts
+           SET max_revisions = max_revisions + $1, agreed_price_lamports = agreed_price_lamports + $2
+           WHERE id = $3 RETURNING *"#,
+    )
+    .bind(amendment.additional_revisions)
+    .bind(amendment.additional_price_lamports)
+    .bind(contract.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE contract_amendments SET status = 'accepted', decided_at = now() WHERE id = $1")
+        .bind(amendment.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/contracts/:id/amendments/:amendment_id/decline — agent owner declines
+pub async fn decline_amendment(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((id, amendment_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<ContractAmendment>> {
+    let mut tx = state.db.begin().await?;
+    let (_contract, amendment) = pending_amendment_for_agent(&mut tx, id, amendment_id, claims.sub).await?;
+
+    let updated = sqlx::query_as::<_, ContractAmendment>(
+        "UPDATE contract_amendments SET status = 'declined', decided_at = now() WHERE id = $1 RETURNING *"
+    )
+    .bind(amendment.id)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// Revision rounds already spent on a contract (rejections + revision requests).
+/// Returns the count, or a Conflict once the offer's allowance is used up.
+async fn ensure_revision_available(conn: &mut PgConnection, contract: &Contract) -> AppResult<i64> {
+    let used: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM submissions WHERE contract_id = $1 AND status IN ('rejected', 'revision_requested')"
+    )
+    .bind(contract.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if used.0 >= contract.max_revisions as i64 {
+        return Err(AppError::Conflict(format!(
+            "Revision limit reached ({}/{}); approve the work, propose an amendment or open a dispute",
+            used.0, contract.max_revisions
+        )));
+    }
+
+    Ok(used.0)
+}
+
+/// Send the job back to the agent: escrow locked → funded, job submitted → in_progress.
+async fn reopen_for_rework(conn: &mut PgConnection, contract: &Contract) -> AppResult<()> {
+    escrow::unlock_escrow(conn, contract.id).await?;
+
+    states::set_job_state_by_id(conn, contract.job_id, JobState::InProgress).await?;
+
+    Ok(())
+}
+
+/// Lock an active contract for the agent's owner, plus one of its pending amendments.
+async fn pending_amendment_for_agent(
+    conn: &mut PgConnection,
+    contract_id: Uuid,
+    amendment_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<(Contract, ContractAmendment)> {
+    let contract = escrow::lock_contract(conn, contract_id).await?;
+
+    sqlx::query("SELECT id FROM agents WHERE id = $1 AND owner_id = $2")
+        .bind(contract.agent_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::Forbidden("Only the contracted agent's owner can answer amendments".into()))?;
+
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest("Contract is not active".into()));
+    }
+
+    let amendment = sqlx::query_as::<_, ContractAmendment>(
+        "SELECT * FROM contract_amendments WHERE id = $1 AND contract_id = $2 FOR UPDATE"
+    )
+    .bind(amendment_id)
+    .bind(contract_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Amendment not found".into()))?;
+
+    if amendment.status != "pending" {
+        return Err(AppError::BadRequest("Amendment has already been answered".into()));
+    }
+
+    Ok((contract, amendment))
+}
+
+/// Lock a contract owned by `client_id` whose job is awaiting review, plus its pending submission.
+async fn pending_submission_for_client(
+    conn: &mut PgConnection,
+    contract_id: Uuid,
+    client_id: Uuid,
+) -> AppResult<(Contract, Submission)> {
+    let contract = escrow::lock_contract(conn, contract_id).await?;
+
+    if contract.client_id != client_id {
+        return Err(AppError::Forbidden("Not your contract".into()));
+    }
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest("Contract is not active".into()));
+    }
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(contract.job_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if job.state != JobState::Submitted {
+        return Err(AppError::BadRequest("No submitted work awaiting review".into()));
+    }
+
+    let submission = sqlx::query_as::<_, Submission>(
+        r#"SELECT * FROM submissions
+           WHERE contract_id = $1 AND status = 'pending'
+           ORDER BY created_at DESC LIMIT 1 FOR UPDATE"#,
+    )
+    .bind(contract.id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("No pending submission on this contract".into()))?;
+
+    Ok((contract, submission))
+}
+
+/// Record a contract-level delivery: locks escrow and moves the job to submitted.
+/// Shared by the agent owner's submit route and agent dispatch callbacks.
+pub async fn deliver_work(conn: &mut PgConnection, contract: &Contract, body: &SubmitWorkReq) -> AppResult<Submission> {
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest("Contract is not active".into()));
+    }
+    if milestones::is_milestone_contract(conn, contract.id).await? {
+        return Err(AppError::BadRequest("This contract is paid per milestone; submit against a milestone".into()));
+    }
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(contract.job_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if job.state != JobState::InProgress {
+        return Err(AppError::BadRequest(format!(
+            "Cannot submit work while job is '{}'", job.state
+        )));
+    }
+
+    // A failed mandatory check blocks the submission before escrow moves
+    let checks = requirement_checks::check_submission(conn, job.id, &body.content, body.artifacts_url.as_deref()).await?;
+
+    // funded → locked; fails if the client has not funded escrow yet
+    escrow::lock_escrow(conn, contract.id).await?;
+
+    let submission = sqlx::query_as::<_, Submission>(
+        r#"INSERT INTO submissions (job_id, contract_id, agent_id, content, artifacts_url, status)
+           VALUES ($1, $2, $3, $4, $5, 'pending')
+           RETURNING *"#,
+    )
+    .bind(contract.job_id)
+    .bind(contract.id)
+    .bind(contract.agent_id)
+    .bind(&body.content)
+    .bind(&body.artifacts_url)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    requirement_checks::record(conn, submission.id, &checks).await?;
+
+    states::set_job_state_by_id(conn, contract.job_id, JobState::Submitted).await?;
+
+    Ok(submission)
+}
+
+/// Which side of the contract `user_id` is on: "client" or "agent" (the agent's owner).
+/// Anyone else is Forbidden.
+pub async fn party_role(state: &AppState, contract: &Contract, user_id: Uuid) -> AppResult<&'static str> {
+    if contract.client_id == user_id {
+        return Ok("client");
+    }
+
+    sqlx::query("SELECT id FROM agents WHERE id = $1 AND owner_id = $2")
+        .bind(contract.agent_id)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::Forbidden("You are not a party to this contract".into()))?;
+
+    Ok("agent")
+}
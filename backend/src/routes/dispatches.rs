@@ -0,0 +1,107 @@
+use axum::{extract::Path, extract::State, http::HeaderMap, Json};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::contracts::{self, party_role};
+use crate::routes::milestones;
+use crate::services::{dispatch, escrow, notifications};
+use crate::AppState;
+
+/// GET /api/contracts/:id/dispatches — delivery attempts to the agent's endpoint (client or agent owner)
+pub async fn list_contract_dispatches(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<JobDispatch>>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let dispatches = sqlx::query_as::<_, JobDispatch>(
+        "SELECT * FROM job_dispatches WHERE contract_id = $1 ORDER BY created_at ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(dispatches))
+}
+
+/// POST /api/dispatches/:id/callback — agent posts its result for a dispatch; becomes a submission.
+/// Authenticated by the dispatch's callback_token in the X-Callback-Token header.
+pub async fn dispatch_callback(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(body): Json<SubmitWorkReq>,
+) -> AppResult<Json<Submission>> {
+    let token = headers
+        .get("X-Callback-Token")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-Callback-Token header".into()))?;
+
+    if !dispatch::verify_callback_token(&state.config.dispatch_signing_secret, id, token) {
+        return Err(AppError::Unauthorized("Invalid callback token".into()));
+    }
+    if body.content.trim().is_empty() {
+        return Err(AppError::BadRequest("Submission content is required".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let job_dispatch = sqlx::query_as::<_, JobDispatch>("SELECT * FROM job_dispatches WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Dispatch not found".into()))?;
+
+    if job_dispatch.status == "completed" {
+        return Err(AppError::Conflict("A result was already recorded for this dispatch".into()));
+    }
+
+    let submission = match job_dispatch.milestone_id {
+        Some(milestone_id) => {
+            let (contract, milestone) =
+                milestones::load_milestone(&mut tx, job_dispatch.contract_id, milestone_id).await?;
+            milestones::deliver_milestone(&mut tx, &contract, &milestone, &body).await?
+        }
+        None => {
+            let contract = escrow::lock_contract(&mut tx, job_dispatch.contract_id).await?;
+            contracts::deliver_work(&mut tx, &contract, &body).await?
+        }
+    };
+
+    sqlx::query(
+        r#"UPDATE job_dispatches
+           SET status = 'completed', submission_id = $1, completed_at = now(),
+               delivered_at = COALESCE(delivered_at, now())
+           WHERE id = $2"#,
+    )
+    .bind(submission.id)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let client_id: (Uuid,) = sqlx::query_as("SELECT client_id FROM contracts WHERE id = $1")
+        .bind(job_dispatch.contract_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    notifications::notify(
+        &state.db,
+        client_id.0,
+        "work_submitted",
+        "The agent delivered its result; it is ready for your review",
+        serde_json::json!({"contract_id": job_dispatch.contract_id, "submission_id": submission.id}),
+    )
+    .await;
+
+    Ok(Json(submission))
+}
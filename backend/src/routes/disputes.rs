@@ -0,0 +1,190 @@
+use axum::{extract::Path, extract::State, Json};
+use uuid::Uuid;
+
+use crate::auth::jwt::Claims;
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::contracts::party_role;
+use crate::services::escrow;
+use crate::states::{self, ContractStatus, JobState};
+use crate::AppState;
+
+/// POST /api/disputes — open a dispute on a contract (client or agent owner).
+/// Freezes escrow and moves contract + job to `disputed`.
+pub async fn open_dispute(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(body): Json<DisputeReq>,
+) -> AppResult<Json<Dispute>> {
+    if body.reason.trim().len() < 20 {
+        return Err(AppError::BadRequest("Reason must be at least 20 characters".into()));
+    }
+    let evidence_links = body.evidence_links.clone().unwrap_or_default();
+    validate_links(&evidence_links)?;
+
+    let mut tx = state.db.begin().await?;
+    let contract = escrow::lock_contract(&mut tx, body.contract_id).await?;
+
+    let role = party_role(&state, &contract, claims.sub).await?;
+
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest(format!(
+            "Cannot dispute a contract in status '{}'", contract.status
+        )));
+    }
+
+    let existing = sqlx::query_as::<_, Dispute>(
+        "SELECT * FROM disputes WHERE contract_id = $1 AND status IN ('open', 'under_review')"
+    )
+    .bind(contract.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if existing.is_some() {
+        return Err(AppError::Conflict("This contract already has an open dispute".into()));
+    }
+
+    // funded/locked → frozen; nothing to dispute if escrow was never funded
+    escrow::freeze_escrow(&mut tx, contract.id).await?;
+
+    let dispute = sqlx::query_as::<_, Dispute>(
+        r#"INSERT INTO disputes (contract_id, initiator_id, reason, evidence_links)
+           VALUES ($1, $2, $3, $4)
+           RETURNING *"#,
+    )
+    .bind(contract.id)
+    .bind(claims.sub)
+    .bind(&body.reason)
+    .bind(&evidence_links)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    states::set_contract_status(&mut tx, &contract, ContractStatus::Disputed).await?;
+    states::set_job_state_by_id(&mut tx, contract.job_id, JobState::Disputed).await?;
+
+    sqlx::query(
+        r#"INSERT INTO audit_logs (user_id, action, entity_type, entity_id, metadata)
+           VALUES ($1, 'dispute_opened', 'dispute', $2, $3)"#,
+    )
+    .bind(claims.sub)
+    .bind(dispute.id)
+    .bind(serde_json::json!({"contract_id": contract.id, "initiator_role": role}))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(dispute))
+}
+
+/// GET /api/disputes/my — disputes on contracts where you are the client or agent owner
+pub async fn my_disputes(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> AppResult<Json<Vec<Dispute>>> {
+    let disputes = sqlx::query_as::<_, Dispute>(
+        r#"SELECT d.* FROM disputes d
+           JOIN contracts c ON d.contract_id = c.id
+           JOIN agents a ON c.agent_id = a.id
+           WHERE c.client_id = $1 OR a.owner_id = $1
+           ORDER BY d.created_at DESC"#,
+    )
+    .bind(claims.sub)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(disputes))
+}
+
+/// GET /api/disputes/:id — dispute with its statement thread (parties and moderators)
+pub async fn get_dispute(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<DisputeView>> {
+    let (dispute, contract, _role) = load_for_participant(&state, id, &claims).await?;
+
+    let statements = sqlx::query_as::<_, DisputeStatement>(
+        "SELECT * FROM dispute_statements WHERE dispute_id = $1 ORDER BY created_at ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(DisputeView {
+        dispute,
+        contract,
+        statements,
+    }))
+}
+
+/// POST /api/disputes/:id/statements — add a statement to the thread until the dispute is resolved
+pub async fn add_statement(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<DisputeStatementReq>,
+) -> AppResult<Json<DisputeStatement>> {
+    if body.body.trim().is_empty() {
+        return Err(AppError::BadRequest("Statement cannot be empty".into()));
+    }
+    let evidence_links = body.evidence_links.clone().unwrap_or_default();
+    validate_links(&evidence_links)?;
+
+    let (dispute, _contract, role) = load_for_participant(&state, id, &claims).await?;
+
+    if !["open", "under_review"].contains(&dispute.status.as_str()) {
+        return Err(AppError::BadRequest("Dispute is closed".into()));
+    }
+
+    let statement = sqlx::query_as::<_, DisputeStatement>(
+        r#"INSERT INTO dispute_statements (dispute_id, author_id, author_role, body, evidence_links)
+           VALUES ($1, $2, $3, $4, $5)
+           RETURNING *"#,
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .bind(role)
+    .bind(&body.body)
+    .bind(&evidence_links)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(statement))
+}
+
+/// Load a dispute + contract and the caller's role in it: "client", "agent" or "moderator".
+async fn load_for_participant(
+    state: &AppState,
+    dispute_id: Uuid,
+    claims: &Claims,
+) -> AppResult<(Dispute, Contract, &'static str)> {
+    let dispute = sqlx::query_as::<_, Dispute>("SELECT * FROM disputes WHERE id = $1")
+        .bind(dispute_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Dispute not found".into()))?;
+
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(dispute.contract_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    let role = if claims.role == "admin" || claims.role == "moderator" {
+        "moderator"
+    } else {
+        party_role(state, &contract, claims.sub).await?
+    };
+
+    Ok((dispute, contract, role))
+}
+
+fn validate_links(links: &[String]) -> AppResult<()> {
+    if links.len() > 20 {
+        return Err(AppError::BadRequest("At most 20 evidence links".into()));
+    }
+    if links.iter().any(|l| !(l.starts_with("https://") || l.starts_with("http://"))) {
+        return Err(AppError::BadRequest("Evidence links must be http(s) URLs".into()));
+    }
+    Ok(())
+}
@@ -5,6 +5,7 @@ use uuid::Uuid;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::routes::invites;
 use crate::AppState;
 
 /// POST /api/favorites — add a favorite
@@ -119,12 +120,13 @@ pub async fn list_favorite_jobs(
     State(state): State<AppState>,
     AuthUser(claims): AuthUser,
 ) -> AppResult<Json<Vec<Job>>> {
-    let jobs = sqlx::query_as::<_, Job>(
+    let jobs = sqlx::query_as::<_, Job>(&format!(
         r#"SELECT j.* FROM jobs j
            JOIN favorites f ON f.entity_id = j.id
-           WHERE f.user_id = $1 AND f.entity_type = 'job'
+           WHERE f.user_id = $1 AND f.entity_type = 'job' AND {}
            ORDER BY f.created_at DESC"#,
-    )
+        invites::LISTED_FOR_USER
+    ))
     .bind(claims.sub)
     .fetch_all(&state.db)
     .await?;
@@ -0,0 +1,175 @@
+use axum::{extract::Path, extract::State, Json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::services::notifications;
+use crate::states::JobState;
+use crate::AppState;
+
+const MAX_INVITES: i64 = 50;
+
+/// SQL predicate: job `j` is listed for user `$1` — public, their own, or one of
+/// their agents is invited. Invite-only jobs stay unlisted for everyone else.
+pub const LISTED_FOR_USER: &str = r#"(j.visibility = 'public' OR j.client_id = $1 OR EXISTS (
+    SELECT 1 FROM job_invites i JOIN agents a ON a.id = i.agent_id
+    WHERE i.job_id = j.id AND a.owner_id = $1))"#;
+
+/// POST /api/jobs/:id/invites — invite agents to bid (job owner, draft or open jobs)
+pub async fn invite_agents(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(job_id): Path<Uuid>,
+    Json(body): Json<InviteAgentsReq>,
+) -> AppResult<Json<Vec<JobInvite>>> {
+    if body.agent_ids.is_empty() {
+        return Err(AppError::BadRequest("agent_ids is required".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE")
+        .bind(job_id)
+        .bind(claims.sub)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+
+    if !matches!(job.state, JobState::Draft | JobState::Open) {
+        return Err(AppError::BadRequest("Agents can only be invited to draft or open jobs".into()));
+    }
+
+    let (existing,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM job_invites WHERE job_id = $1")
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let agents = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = ANY($1) AND status = 'active'")
+        .bind(&body.agent_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+    if let Some(missing) = body.agent_ids.iter().find(|id| !agents.iter().any(|a| a.id == **id)) {
+        return Err(AppError::NotFound(format!("Agent {missing} not found or not active")));
+    }
+
+    let mut invites = Vec::new();
+    for agent in &agents {
+        let invite = sqlx::query_as::<_, JobInvite>(
+            r#"INSERT INTO job_invites (job_id, agent_id, invited_by) VALUES ($1, $2, $3)
+               ON CONFLICT (job_id, agent_id) DO NOTHING
+               RETURNING *"#,
+        )
+        .bind(job_id)
+        .bind(agent.id)
+        .bind(claims.sub)
+        .fetch_optional(&mut *tx)
+        .await?;
+        invites.extend(invite);
+    }
+
+    if existing + invites.len() as i64 > MAX_INVITES {
+        return Err(AppError::BadRequest(format!("At most {MAX_INVITES} agents can be invited to a job")));
+    }
+    tx.commit().await?;
+
+    for invite in &invites {
+        if let Some(agent) = agents.iter().find(|a| a.id == invite.agent_id) {
+            notifications::notify(
+                &state.db,
+                agent.owner_id,
+                "job_invite",
+                &format!("Your agent {} was invited to bid on \"{}\"", agent.name, job.title),
+                serde_json::json!({"job_id": job_id, "agent_id": agent.id}),
+            )
+            .await;
+        }
+    }
+
+    Ok(Json(invites))
+}
+
+/// GET /api/jobs/:id/invites — agents invited to a job (job owner)
+pub async fn list_invites(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<Vec<JobInvite>>> {
+    let invites = sqlx::query_as::<_, JobInvite>(
+        r#"SELECT i.* FROM job_invites i
+           JOIN jobs j ON j.id = i.job_id
+           WHERE i.job_id = $1 AND j.client_id = $2
+           ORDER BY i.created_at"#,
+    )
+    .bind(job_id)
+    .bind(claims.sub)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(invites))
+}
+
+/// GET /api/jobs/invited — open jobs any of the caller's agents is invited to
+pub async fn invited_jobs(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> AppResult<Json<Vec<Job>>> {
+    let jobs = sqlx::query_as::<_, Job>(
+        r#"SELECT DISTINCT j.* FROM jobs j
+           JOIN job_invites i ON i.job_id = j.id
+           JOIN agents a ON a.id = i.agent_id
+           WHERE a.owner_id = $1 AND j.state = 'open'
+           ORDER BY j.created_at DESC"#,
+    )
+    .bind(claims.sub)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(jobs))
+}
+
+/// Private jobs are only visible to their client, the owners of invited agents and
+/// invited battle judges; public and invite-only jobs are readable by anyone with the link.
+pub async fn can_view(db: &PgPool, job: &Job, user_id: Option<Uuid>) -> AppResult<bool> {
+    if job.visibility != "private" {
+        return Ok(true);
+    }
+    let Some(user_id) = user_id else {
+        return Ok(false);
+    };
+    if user_id == job.client_id {
+        return Ok(true);
+    }
+
+    let (invited,): (bool,) = sqlx::query_as(
+        r#"SELECT EXISTS (SELECT 1 FROM job_invites i JOIN agents a ON a.id = i.agent_id
+                          WHERE i.job_id = $1 AND a.owner_id = $2)
+               OR EXISTS (SELECT 1 FROM battle_judges WHERE job_id = $1 AND user_id = $2)"#,
+    )
+    .bind(job.id)
+    .bind(user_id)
+    .fetch_one(db)
+    .await?;
+    Ok(invited)
+}
+
+/// Only invited agents may bid on invite-only and private jobs.
+pub async fn check_can_bid(db: &PgPool, job: &Job, agent_id: Uuid) -> AppResult<()> {
+    if job.visibility == "public" {
+        return Ok(());
+    }
+
+    let (invited,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM job_invites WHERE job_id = $1 AND agent_id = $2)"
+    )
+    .bind(job.id)
+    .bind(agent_id)
+    .fetch_one(db)
+    .await?;
+
+    if invited {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden("This job only takes offers from invited agents".into()))
+    }
+}
@@ -1,55 +1,94 @@
 use axum::{extract::Path, extract::Query, extract::State, Json};
+use serde::Serialize;
 use uuid::Uuid;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::{AuthUser, OptionalAuth};
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::routes::invites;
+use crate::services::{job_templates, notifications, requirement_checks};
+use crate::states::{self, JobState};
 use crate::AppState;
 
+/// Edits to these fields on an open job are announced to agents with pending offers.
+const MATERIAL_FIELDS: [&str; 3] = ["description", "budget_lamports", "deadline"];
+
 /// POST /api/jobs — create a new job (draft)
 pub async fn create_job(
     State(state): State<AppState>,
     AuthUser(claims): AuthUser,
     Json(body): Json<CreateJobReq>,
 ) -> AppResult<Json<Job>> {
-    let currency = body.currency.as_deref().unwrap_or("USDC");
-    let currency_chain = body.currency_chain.as_deref().unwrap_or("solana");
+    let mut conn = state.db.acquire().await?;
+    let job = job_templates::insert_job(&mut conn, claims.sub, &body).await?;
+
+    Ok(Json(job))
+}
 
+/// POST /api/jobs/:id/clone — new draft with the job's settings, requirements and invites.
+/// Deadlines that have already passed are dropped.
+pub async fn clone_job(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Job>> {
     let job = sqlx::query_as::<_, Job>(
-        r#"INSERT INTO jobs (client_id, title, description, budget_lamports, currency, currency_chain,
-           battle_mode, battle_max_submissions, battle_partial_reward_pct, deadline, tags)
-           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-           RETURNING *"#,
+        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2"
     )
+    .bind(id)
     .bind(claims.sub)
-    .bind(&body.title)
-    .bind(&body.description)
-    .bind(body.budget_lamports)
-    .bind(currency)
-    .bind(currency_chain)
-    .bind(body.battle_mode.unwrap_or(false))
-    .bind(body.battle_max_submissions)
-    .bind(body.battle_partial_reward_pct)
-    .bind(body.deadline)
-    .bind(&body.tags.clone().unwrap_or_default())
-    .fetch_one(&state.db)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+
+    let requirements = sqlx::query_as::<_, JobRequirement>(
+        "SELECT * FROM job_requirements WHERE job_id = $1"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
     .await?;
 
-    // Insert requirements
-    if let Some(reqs) = &body.requirements {
-        for req in reqs {
-            sqlx::query(
-                "INSERT INTO job_requirements (job_id, requirement, is_mandatory) VALUES ($1, $2, $3)"
-            )
-            .bind(job.id)
-            .bind(&req.requirement)
-            .bind(req.is_mandatory.unwrap_or(true))
-            .execute(&state.db)
-            .await?;
-        }
-    }
+    let now = chrono::Utc::now();
+    let body = CreateJobReq {
+        title: job.title,
+        description: job.description,
+        budget_lamports: job.budget_lamports,
+        currency: Some(job.currency),
+        currency_chain: Some(job.currency_chain),
+        battle_mode: Some(job.battle_mode),
+        battle_max_submissions: job.battle_max_submissions,
+        battle_partial_reward_pct: job.battle_partial_reward_pct,
+        battle_closes_at: job.battle_closes_at.filter(|t| *t > now),
+        deadline: job.deadline.filter(|t| *t > now),
+        tags: job.tags,
+        visibility: Some(job.visibility),
+        requirements: Some(
+            requirements
+                .into_iter()
+                .map(|r| RequirementInput {
+                    requirement: r.requirement,
+                    is_mandatory: Some(r.is_mandatory),
+                    check_type: r.check_type,
+                    check_config: r.check_config,
+                })
+                .collect(),
+        ),
+    };
 
-    Ok(Json(job))
+    let mut tx = state.db.begin().await?;
+    let clone = job_templates::insert_job(&mut tx, claims.sub, &body).await?;
+    sqlx::query(
+        r#"INSERT INTO job_invites (job_id, agent_id, invited_by)
+           SELECT $1, agent_id, $2 FROM job_invites WHERE job_id = $3"#,
+    )
+    .bind(clone.id)
+    .bind(claims.sub)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(clone))
 }
 
 /// POST /api/jobs/:id/publish — transition draft → open
@@ -58,39 +97,33 @@ pub async fn publish_job(
     AuthUser(claims): AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Job>> {
+    let mut conn = state.db.acquire().await?;
     let job = sqlx::query_as::<_, Job>(
         "SELECT * FROM jobs WHERE id = $1 AND client_id = $2"
     )
     .bind(id)
     .bind(claims.sub)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *conn)
     .await?
     .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
 
-    if job.state != "draft" {
-        return Err(AppError::BadRequest(format!(
-            "Cannot publish job in state '{}'", job.state
-        )));
+    if job.battle_closes_at.is_some_and(|t| t <= chrono::Utc::now()) {
+        return Err(AppError::BadRequest("battle_closes_at has already passed".into()));
     }
 
-    let updated = sqlx::query_as::<_, Job>(
-        "UPDATE jobs SET state = 'open', updated_at = now() WHERE id = $1 RETURNING *"
-    )
-    .bind(id)
-    .fetch_one(&state.db)
-    .await?;
+    let updated = states::set_job_state(&mut conn, &job, JobState::Open).await?;
 
     Ok(Json(updated))
 }
 
-/// GET /api/jobs — browse open jobs with sort
+/// GET /api/jobs — browse open public jobs with sort
 /// Sort options: date (default), budget, title
 pub async fn list_jobs(
     State(state): State<AppState>,
     Query(params): Query<PaginationParams>,
 ) -> AppResult<Json<PaginatedResponse<Job>>> {
     let total: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM jobs WHERE state = 'open'"
+        "SELECT COUNT(*) FROM jobs WHERE state = 'open' AND visibility = 'public'"
     )
     .fetch_one(&state.db)
     .await?;
@@ -104,7 +137,7 @@ pub async fn list_jobs(
     let dir = params.order_dir();
 
     let query = format!(
-        "SELECT * FROM jobs WHERE state = 'open' ORDER BY {} {} NULLS LAST LIMIT $1 OFFSET $2",
+        "SELECT * FROM jobs WHERE state = 'open' AND visibility = 'public' ORDER BY {} {} NULLS LAST LIMIT $1 OFFSET $2",
         order_col, dir
     );
 
@@ -122,21 +155,24 @@ pub async fn list_jobs(
     }))
 }
 
-/// GET /api/jobs/all — browse all jobs (any state, for authenticated users)
+/// GET /api/jobs/all — browse all jobs (any state, for authenticated users).
+/// Restricted jobs only show to their client and invited agents' owners.
 pub async fn list_all_jobs(
     State(state): State<AppState>,
-    AuthUser(_claims): AuthUser,
+    AuthUser(claims): AuthUser,
     Query(params): Query<PaginationParams>,
 ) -> AppResult<Json<PaginatedResponse<Job>>> {
     let total: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM jobs"
+        &format!("SELECT COUNT(*) FROM jobs j WHERE {}", invites::LISTED_FOR_USER)
     )
+    .bind(claims.sub)
     .fetch_one(&state.db)
     .await?;
 
     let jobs = sqlx::query_as::<_, Job>(
-        "SELECT * FROM jobs ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+        &format!("SELECT j.* FROM jobs j WHERE {} ORDER BY j.created_at DESC LIMIT $2 OFFSET $3", invites::LISTED_FOR_USER)
     )
+    .bind(claims.sub)
     .bind(params.limit())
     .bind(params.offset())
     .fetch_all(&state.db)
@@ -150,25 +186,197 @@ pub async fn list_all_jobs(
     }))
 }
 
-/// GET /api/jobs/:id
+/// GET /api/jobs/:id — private jobs only for their client and invited agents' owners and battle judges.
+/// Edited jobs carry the diff of their latest edit.
 pub async fn get_job(
     State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
     Path(id): Path<Uuid>,
-) -> AppResult<Json<Job>> {
-    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+) -> AppResult<Json<JobView>> {
+    let job = visible_job(&state, id, claims.map(|c| c.sub)).await?;
+
+    let last_edit = match job.edited_at {
+        Some(_) => sqlx::query_as::<_, JobRevision>(
+            "SELECT * FROM job_revisions WHERE job_id = $1 ORDER BY revision DESC LIMIT 1"
+        )
         .bind(id)
         .fetch_optional(&state.db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+        .await?,
+        None => None,
+    };
 
-    Ok(Json(job))
+    Ok(Json(JobView { job, last_edit }))
+}
+
+/// PATCH /api/jobs/:id — edit a draft, or the title, description, budget, deadline
+/// and tags of an open job. Material edits to an open job notify agents with pending offers.
+pub async fn update_job(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateJobReq>,
+) -> AppResult<Json<Job>> {
+    let mut tx = state.db.begin().await?;
+    let job = sqlx::query_as::<_, Job>(
+        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE"
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+
+    match job.state {
+        JobState::Draft => {}
+        JobState::Open => {
+            let locked: Vec<&str> = [
+                ("currency", body.currency.is_some()),
+                ("currency_chain", body.currency_chain.is_some()),
+                ("battle_mode", body.battle_mode.is_some()),
+                ("battle_max_submissions", body.battle_max_submissions.is_some()),
+                ("battle_partial_reward_pct", body.battle_partial_reward_pct.is_some()),
+                ("battle_closes_at", body.battle_closes_at.is_some()),
+                ("visibility", body.visibility.is_some()),
+            ]
+            .into_iter()
+            .filter_map(|(field, set)| set.then_some(field))
+            .collect();
+            if !locked.is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "Only drafts can change: {}",
+                    locked.join(", ")
+                )));
+            }
+        }
+        _ => return Err(AppError::BadRequest("Only draft and open jobs can be edited".into())),
+    }
+
+    if body.title.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return Err(AppError::BadRequest("title can't be empty".into()));
+    }
+    if body.description.as_deref().is_some_and(|d| d.trim().is_empty()) {
+        return Err(AppError::BadRequest("description can't be empty".into()));
+    }
+    if body.budget_lamports.flatten().is_some_and(|b| b <= 0) {
+        return Err(AppError::BadRequest("budget_lamports must be positive".into()));
+    }
+    // A past deadline would make every contract from the job late on acceptance
+    if body.deadline.flatten().is_some_and(|t| t <= chrono::Utc::now()) {
+        return Err(AppError::BadRequest("deadline must be in the future".into()));
+    }
+    job_templates::validate_settings(
+        body.visibility.as_deref().unwrap_or(&job.visibility),
+        body.battle_partial_reward_pct.flatten(),
+        body.battle_closes_at.flatten(),
+    )?;
+
+    let mut edited = job.clone();
+    let mut changes = serde_json::Map::new();
+    track(&mut changes, "title", &mut edited.title, body.title);
+    track(&mut changes, "description", &mut edited.description, body.description);
+    track(&mut changes, "budget_lamports", &mut edited.budget_lamports, body.budget_lamports);
+    track(&mut changes, "currency", &mut edited.currency, body.currency);
+    track(&mut changes, "currency_chain", &mut edited.currency_chain, body.currency_chain);
+    track(&mut changes, "battle_mode", &mut edited.battle_mode, body.battle_mode);
+    track(&mut changes, "battle_max_submissions", &mut edited.battle_max_submissions, body.battle_max_submissions);
+    track(&mut changes, "battle_partial_reward_pct", &mut edited.battle_partial_reward_pct, body.battle_partial_reward_pct);
+    track(&mut changes, "battle_closes_at", &mut edited.battle_closes_at, body.battle_closes_at);
+    track(&mut changes, "deadline", &mut edited.deadline, body.deadline);
+    track(&mut changes, "tags", &mut edited.tags, body.tags.map(Some));
+    track(&mut changes, "visibility", &mut edited.visibility, body.visibility);
+
+    if changes.is_empty() {
+        return Err(AppError::BadRequest("Nothing to change".into()));
+    }
+
+    let updated = sqlx::query_as::<_, Job>(
+        r#"UPDATE jobs SET title = $1, description = $2, budget_lamports = $3, currency = $4, currency_chain = $5,
+           battle_mode = $6, battle_max_submissions = $7, battle_partial_reward_pct = $8, battle_closes_at = $9,
+           deadline = $10, tags = $11, visibility = $12, edited_at = now(), updated_at = now()
+           WHERE id = $13
+           RETURNING *"#,
+    )
+    .bind(&edited.title)
+    .bind(&edited.description)
+    .bind(edited.budget_lamports)
+    .bind(&edited.currency)
+    .bind(&edited.currency_chain)
+    .bind(edited.battle_mode)
+    .bind(edited.battle_max_submissions)
+    .bind(edited.battle_partial_reward_pct)
+    .bind(edited.battle_closes_at)
+    .bind(edited.deadline)
+    .bind(&edited.tags)
+    .bind(&edited.visibility)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let is_material = MATERIAL_FIELDS.iter().any(|f| changes.contains_key(*f));
+    let fields: Vec<String> = changes.keys().cloned().collect();
+    sqlx::query(
+        r#"INSERT INTO job_revisions (job_id, revision, edited_by, changes, is_material)
+           VALUES ($1, (SELECT COALESCE(MAX(revision), 0) + 1 FROM job_revisions WHERE job_id = $1), $2, $3, $4)"#,
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .bind(serde_json::Value::Object(changes))
+    .bind(is_material)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    if is_material && job.state == JobState::Open {
+        let owners: Vec<(Uuid,)> = sqlx::query_as(
+            r#"SELECT DISTINCT a.owner_id FROM offers o
+               JOIN agents a ON a.id = o.agent_id
+               WHERE o.job_id = $1 AND o.status = 'pending'"#,
+        )
+        .bind(id)
+        .fetch_all(&state.db)
+        .await?;
+
+        for (owner_id,) in owners {
+            notifications::notify(
+                &state.db,
+                owner_id,
+                "job_edited",
+                &format!("\"{}\", which you have an offer on, changed: {}", updated.title, fields.join(", ")),
+                serde_json::json!({"job_id": id, "fields": fields}),
+            )
+            .await;
+        }
+    }
+
+    Ok(Json(updated))
+}
+
+/// GET /api/jobs/:id/revisions — edit history, oldest first, each with its field diff
+pub async fn list_job_revisions(
+    State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<JobRevision>>> {
+    visible_job(&state, id, claims.map(|c| c.sub)).await?;
+
+    let revisions = sqlx::query_as::<_, JobRevision>(
+        "SELECT * FROM job_revisions WHERE job_id = $1 ORDER BY revision ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(revisions))
 }
 
 /// GET /api/jobs/:id/requirements
 pub async fn get_job_requirements(
     State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Vec<JobRequirement>>> {
+    visible_job(&state, id, claims.map(|c| c.sub)).await?;
+
     let reqs = sqlx::query_as::<_, JobRequirement>(
         "SELECT * FROM job_requirements WHERE job_id = $1"
     )
@@ -185,25 +393,18 @@ pub async fn cancel_job(
     AuthUser(claims): AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Job>> {
+    let mut conn = state.db.acquire().await?;
     let job = sqlx::query_as::<_, Job>(
         "SELECT * FROM jobs WHERE id = $1 AND client_id = $2"
     )
     .bind(id)
     .bind(claims.sub)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *conn)
     .await?
     .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
 
-    if !["draft", "open"].contains(&job.state.as_str()) {
-        return Err(AppError::BadRequest("Can only cancel draft or open jobs".into()));
-    }
-
-    let updated = sqlx::query_as::<_, Job>(
-        "UPDATE jobs SET state = 'cancelled', updated_at = now() WHERE id = $1 RETURNING *"
-    )
-    .bind(id)
-    .fetch_one(&state.db)
-    .await?;
+    // Conditional update: loses cleanly to a concurrent accept_offer
+    let updated = states::set_job_state(&mut conn, &job, JobState::Cancelled).await?;
 
     Ok(Json(updated))
 }
@@ -223,5 +424,44 @@ pub async fn my_jobs(
     Ok(Json(jobs))
 }
 
+/// POST /api/jobs/:id/check — dry-run the job's machine checks against candidate work
+pub async fn check_requirements(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SubmitWorkReq>,
+) -> AppResult<Json<Vec<RequirementCheckResult>>> {
+    visible_job(&state, id, Some(claims.sub)).await?;
 
+    let mut conn = state.db.acquire().await?;
+    let results = requirement_checks::evaluate(&mut conn, id, &body.content, body.artifacts_url.as_deref()).await?;
+
+    Ok(Json(results))
+}
 
+/// Load a job, hiding private jobs from users who may not see them.
+async fn visible_job(state: &AppState, id: Uuid, user_id: Option<Uuid>) -> AppResult<Job> {
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+
+    if !invites::can_view(&state.db, &job, user_id).await? {
+        return Err(AppError::NotFound("Job not found".into()));
+    }
+    Ok(job)
+}
+
+/// Apply `new` to `current` and record the change as `{from, to}` if it differs.
+fn track<T: PartialEq + Serialize>(
+    changes: &mut serde_json::Map<String, serde_json::Value>,
+    field: &str,
+    current: &mut T,
+    new: Option<T>,
+) {
+    if let Some(new) = new.filter(|v| v != current) {
+        changes.insert(field.into(), serde_json::json!({"from": &*current, "to": &new}));
+        *current = new;
+    }
+}
@@ -0,0 +1,329 @@
+use axum::{extract::Path, extract::State, Json};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::auth::middleware::{AuthUser, OptionalAuth};
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::battle::is_closed;
+use crate::routes::invites;
+use crate::services::notifications;
+use crate::states::JobState;
+use crate::AppState;
+
+const MAX_CRITERIA: usize = 20;
+const MAX_JUDGES: i64 = 10;
+
+/// GET /api/battle/:job_id/rubric — scoring criteria and invited judges
+pub async fn get_rubric(
+    State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<BattleRubricView>> {
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+
+    if !invites::can_view(&state.db, &job, claims.map(|c| c.sub)).await? {
+        return Err(AppError::NotFound("Job not found".into()));
+    }
+
+    let criteria = rubric_criteria(&state.db, job_id).await?;
+
+    let judges = sqlx::query_as::<_, BattleJudge>(
+        "SELECT * FROM battle_judges WHERE job_id = $1 ORDER BY created_at"
+    )
+    .bind(job_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(BattleRubricView { criteria, judges }))
+}
+
+/// POST /api/battle/:job_id/rubric — define (or replace) the rubric; locked once scoring starts
+pub async fn set_rubric(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(job_id): Path<Uuid>,
+    Json(body): Json<SetRubricReq>,
+) -> AppResult<Json<Vec<BattleRubricCriterion>>> {
+    let mut tx = state.db.begin().await?;
+    let job = owned_battle(&mut tx, job_id, claims.sub).await?;
+
+    if matches!(job.state, JobState::Completed | JobState::Cancelled) {
+        return Err(AppError::BadRequest("The battle is already decided".into()));
+    }
+
+    let scored: (bool,) = sqlx::query_as(
+        r#"SELECT EXISTS (
+               SELECT 1 FROM battle_scores sc
+               JOIN battle_rubric_criteria c ON c.id = sc.criterion_id
+               WHERE c.job_id = $1
+           )"#,
+    )
+    .bind(job_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if scored.0 {
+        return Err(AppError::Conflict("Judging has started; the rubric can no longer change".into()));
+    }
+
+    // (requirement_id, name, description, weight)
+    let criteria: Vec<(Option<Uuid>, String, Option<String>, f32)> = match body.criteria {
+        Some(criteria) => criteria
+            .into_iter()
+            .map(|c| (None, c.name.trim().to_string(), c.description, c.weight.unwrap_or(1.0)))
+            .collect(),
+        None => sqlx::query_as::<_, JobRequirement>("SELECT * FROM job_requirements WHERE job_id = $1")
+            .bind(job_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|r| (Some(r.id), r.requirement, None, if r.is_mandatory { 2.0 } else { 1.0 }))
+            .collect(),
+    };
+
+    if criteria.is_empty() {
+        return Err(AppError::BadRequest("A rubric needs at least one criterion; the job has no requirements to seed it from".into()));
+    }
+    if criteria.len() > MAX_CRITERIA {
+        return Err(AppError::BadRequest(format!("At most {MAX_CRITERIA} criteria")));
+    }
+    if criteria.iter().any(|(_, name, _, weight)| name.is_empty() || *weight <= 0.0) {
+        return Err(AppError::BadRequest("Every criterion needs a name and a positive weight".into()));
+    }
+
+    sqlx::query("DELETE FROM battle_rubric_criteria WHERE job_id = $1")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut created = Vec::with_capacity(criteria.len());
+    for (position, (requirement_id, name, description, weight)) in criteria.into_iter().enumerate() {
+        let criterion = sqlx::query_as::<_, BattleRubricCriterion>(
+            r#"INSERT INTO battle_rubric_criteria (job_id, requirement_id, position, name, description, weight)
+               VALUES ($1, $2, $3, $4, $5, $6) RETURNING *"#,
+        )
+        .bind(job_id)
+        .bind(requirement_id)
+        .bind(position as i32 + 1)
+        .bind(name)
+        .bind(description)
+        .bind(weight)
+        .fetch_one(&mut *tx)
+        .await?;
+        created.push(criterion);
+    }
+    tx.commit().await?;
+
+    Ok(Json(created))
+}
+
+/// POST /api/battle/:job_id/judges — invite another user to score entries
+pub async fn invite_judge(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(job_id): Path<Uuid>,
+    Json(body): Json<InviteJudgeReq>,
+) -> AppResult<Json<BattleJudge>> {
+    let mut tx = state.db.begin().await?;
+    let job = owned_battle(&mut tx, job_id, claims.sub).await?;
+
+    if body.user_id == job.client_id {
+        return Err(AppError::BadRequest("The job owner always judges".into()));
+    }
+
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(body.user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM battle_judges WHERE job_id = $1")
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    if count.0 >= MAX_JUDGES {
+        return Err(AppError::BadRequest(format!("At most {MAX_JUDGES} invited judges")));
+    }
+
+    let judge = sqlx::query_as::<_, BattleJudge>(
+        r#"INSERT INTO battle_judges (job_id, user_id, invited_by) VALUES ($1, $2, $3)
+           ON CONFLICT (job_id, user_id) DO NOTHING
+           RETURNING *"#,
+    )
+    .bind(job_id)
+    .bind(user.id)
+    .bind(claims.sub)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict("User is already a judge".into()))?;
+    tx.commit().await?;
+
+    notifications::notify(
+        &state.db,
+        user.id,
+        "battle_judge_invited",
+        &format!("You were invited to judge the battle \"{}\"", job.title),
+        serde_json::json!({"job_id": job_id}),
+    )
+    .await;
+
+    Ok(Json(judge))
+}
+
+/// POST /api/battle/submissions/:id/scores — score an entry against the rubric (judges, once closed).
+/// Re-scoring a criterion overwrites the judge's earlier score.
+pub async fn score_submission(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ScoreSubmissionReq>,
+) -> AppResult<Json<Vec<BattleScore>>> {
+    if body.scores.is_empty() {
+        return Err(AppError::BadRequest("No scores given".into()));
+    }
+    if body.scores.iter().any(|s| !(0..=10).contains(&s.score)) {
+        return Err(AppError::BadRequest("Scores must be between 0 and 10".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let submission = sqlx::query_as::<_, Submission>(
+        "SELECT * FROM submissions WHERE id = $1 AND is_battle_submission = true"
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Battle submission not found".into()))?;
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1 FOR UPDATE")
+        .bind(submission.job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    if !is_judge(&mut tx, &job, claims.sub).await? {
+        return Err(AppError::Forbidden("You are not a judge of this battle".into()));
+    }
+    if !is_closed(&job) {
+        return Err(AppError::BadRequest("Entries can be scored once the battle closes".into()));
+    }
+    if matches!(job.state, JobState::Completed | JobState::Cancelled) {
+        return Err(AppError::BadRequest("The battle is already decided".into()));
+    }
+
+    let own_entry: (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM agents WHERE id = $1 AND owner_id = $2)")
+        .bind(submission.agent_id)
+        .bind(claims.sub)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    if own_entry.0 {
+        return Err(AppError::Forbidden("You cannot judge your own agent's entry".into()));
+    }
+
+    let mut scores = Vec::with_capacity(body.scores.len());
+    for input in &body.scores {
+        let score = sqlx::query_as::<_, BattleScore>(
+            r#"INSERT INTO battle_scores (submission_id, criterion_id, judge_id, score, comment)
+               SELECT $1, c.id, $3, $4, $5 FROM battle_rubric_criteria c WHERE c.id = $2 AND c.job_id = $6
+               ON CONFLICT (submission_id, criterion_id, judge_id)
+               DO UPDATE SET score = EXCLUDED.score, comment = EXCLUDED.comment, updated_at = now()
+               RETURNING *"#,
+        )
+        .bind(id)
+        .bind(input.criterion_id)
+        .bind(claims.sub)
+        .bind(input.score)
+        .bind(&input.comment)
+        .bind(job.id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest(format!("Criterion {} is not part of this rubric", input.criterion_id)))?;
+        scores.push(score);
+    }
+
+    rank_entries(&mut tx, job.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(scores))
+}
+
+/// Rubric criteria of a battle, in order.
+pub async fn rubric_criteria(db: &PgPool, job_id: Uuid) -> AppResult<Vec<BattleRubricCriterion>> {
+    Ok(sqlx::query_as::<_, BattleRubricCriterion>(
+        "SELECT * FROM battle_rubric_criteria WHERE job_id = $1 ORDER BY position"
+    )
+    .bind(job_id)
+    .fetch_all(db)
+    .await?)
+}
+
+/// The job owner and invited users judge a battle.
+pub async fn is_judge(conn: &mut PgConnection, job: &Job, user_id: Uuid) -> AppResult<bool> {
+    if user_id == job.client_id {
+        return Ok(true);
+    }
+
+    let invited: (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM battle_judges WHERE job_id = $1 AND user_id = $2)"
+    )
+    .bind(job.id)
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(invited.0)
+}
+
+/// Recompute every entry's `rubric_score` (0-100) and `rubric_rank`. Each criterion
+/// takes the judges' average; unscored criteria count as 0, and earlier entries win ties.
+async fn rank_entries(conn: &mut PgConnection, job_id: Uuid) -> AppResult<()> {
+    sqlx::query(
+        r#"WITH criteria AS (
+               SELECT id, weight FROM battle_rubric_criteria WHERE job_id = $1
+           ),
+           averages AS (
+               SELECT sc.submission_id, sc.criterion_id, AVG(sc.score)::float8 AS avg
+               FROM battle_scores sc JOIN criteria c ON c.id = sc.criterion_id
+               GROUP BY sc.submission_id, sc.criterion_id
+           ),
+           totals AS (
+               SELECT s.id, s.created_at,
+                      COALESCE(SUM(c.weight * a.avg), 0) / (SELECT SUM(weight) FROM criteria) * 10 AS score
+               FROM submissions s
+               LEFT JOIN averages a ON a.submission_id = s.id
+               LEFT JOIN criteria c ON c.id = a.criterion_id
+               WHERE s.job_id = $1 AND s.is_battle_submission
+               GROUP BY s.id, s.created_at
+           )
+           UPDATE submissions s
+           SET rubric_score = t.score::real,
+               rubric_rank = t.rank
+           FROM (SELECT id, score, ROW_NUMBER() OVER (ORDER BY score DESC, created_at)::int AS rank FROM totals) t
+           WHERE s.id = t.id"#,
+    )
+    .bind(job_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn owned_battle(conn: &mut PgConnection, job_id: Uuid, user_id: Uuid) -> AppResult<Job> {
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE")
+        .bind(job_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::Forbidden("Not your job".into()))?;
+
+    if !job.battle_mode {
+        return Err(AppError::BadRequest("Not a battle mode job".into()));
+    }
+
+    Ok(job)
+}
@@ -39,5 +39,34 @@ pub async fn get_leaderboard(
     Ok(Json(entries))
 }
 
+/// GET /api/leaderboard/battle — top agents by battle rating (agents with at least one decided battle)
+pub async fn get_battle_leaderboard(
+    State(state): State<AppState>,
+    Query(params): Query<PaginationParams>,
+) -> AppResult<Json<Vec<LeaderboardEntry>>> {
+    let limit = params.limit().min(50);
+    let offset = params.offset();
 
+    let agents = sqlx::query_as::<_, Agent>(
+        r#"SELECT * FROM agents
+           WHERE status = 'active' AND battles_played > 0
+           ORDER BY battle_rating DESC
+           LIMIT $1 OFFSET $2"#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    let entries: Vec<LeaderboardEntry> = agents
+        .into_iter()
+        .enumerate()
+        .map(|(i, agent)| LeaderboardEntry {
+            rank: offset + i as i64 + 1,
+            score: agent.battle_rating,
+            agent,
+        })
+        .collect();
 
+    Ok(Json(entries))
+}
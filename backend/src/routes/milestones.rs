@@ -0,0 +1,183 @@
+use axum::{extract::Path, extract::State, Json};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::middleware::{AgentAuth, AuthUser};
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::contracts::party_role;
+use crate::services::{dispatch, escrow, requirement_checks};
+use crate::states::{self, ContractStatus, MilestoneState};
+use crate::AppState;
+
+/// GET /api/contracts/:id/milestones — milestones with their current state (client or agent owner)
+pub async fn list_contract_milestones(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<Milestone>>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let milestones = sqlx::query_as::<_, Milestone>(
+        "SELECT * FROM milestones WHERE contract_id = $1 ORDER BY position ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(milestones))
+}
+
+/// POST /api/contracts/:id/milestones/:milestone_id/fund — client funds one tranche (pending → funded)
+pub async fn fund_milestone(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((id, milestone_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Milestone>> {
+    let mut tx = state.db.begin().await?;
+    let (contract, milestone) = load_milestone(&mut tx, id, milestone_id).await?;
+
+    if contract.client_id != claims.sub {
+        return Err(AppError::Forbidden("Only the client can fund milestones".into()));
+    }
+    if milestone.state != MilestoneState::Pending {
+        return Err(AppError::BadRequest(format!(
+            "Milestone is '{}', cannot fund", milestone.state
+        )));
+    }
+
+    escrow::fund_milestone_escrow(&mut tx, &milestone, &contract).await?;
+
+    let updated = states::set_milestone_state(&mut tx, &milestone, MilestoneState::Funded).await?;
+
+    dispatch::enqueue(&mut tx, contract.id, Some(&updated)).await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/contracts/:id/milestones/:milestone_id/submit — agent owner delivers a milestone (funded → submitted)
+pub async fn submit_milestone(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path((id, milestone_id)): Path<(Uuid, Uuid)>,
+    Json(body): Json<SubmitWorkReq>,
+) -> AppResult<Json<Submission>> {
+    if body.content.trim().is_empty() {
+        return Err(AppError::BadRequest("Submission content is required".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let (contract, milestone) = load_milestone(&mut tx, id, milestone_id).await?;
+    caller.check_agent(contract.agent_id)?;
+
+    if party_role(&state, &contract, caller.user_id).await? != "agent" {
+        return Err(AppError::Forbidden("Only the contracted agent's owner can submit work".into()));
+    }
+
+    let submission = deliver_milestone(&mut tx, &contract, &milestone, &body).await?;
+    tx.commit().await?;
+
+    Ok(Json(submission))
+}
+
+/// POST /api/contracts/:id/milestones/:milestone_id/release — client approves a milestone and pays its tranche.
+/// Completes the contract once every milestone is released.
+pub async fn release_milestone(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((id, milestone_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Milestone>> {
+    let mut tx = state.db.begin().await?;
+    let (contract, milestone) = load_milestone(&mut tx, id, milestone_id).await?;
+
+    if contract.client_id != claims.sub {
+        return Err(AppError::Forbidden("Only the client can release milestones".into()));
+    }
+    if milestone.state != MilestoneState::Submitted {
+        return Err(AppError::BadRequest("Milestone has no submitted work to release".into()));
+    }
+
+    let updated = escrow::release_milestone(&mut tx, &contract, milestone.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// Record a milestone delivery (funded → submitted) and lock its tranche.
+/// Shared by the agent owner's submit route and agent dispatch callbacks.
+pub async fn deliver_milestone(
+    conn: &mut PgConnection,
+    contract: &Contract,
+    milestone: &Milestone,
+    body: &SubmitWorkReq,
+) -> AppResult<Submission> {
+    if milestone.state != MilestoneState::Funded {
+        return Err(AppError::BadRequest(format!(
+            "Milestone is '{}', cannot submit", milestone.state
+        )));
+    }
+
+    let checks = requirement_checks::check_submission(conn, contract.job_id, &body.content, body.artifacts_url.as_deref()).await?;
+
+    escrow::lock_milestone_escrow(conn, milestone.id).await?;
+
+    let submission = sqlx::query_as::<_, Submission>(
+        r#"INSERT INTO submissions (job_id, contract_id, milestone_id, agent_id, content, artifacts_url, status)
+           VALUES ($1, $2, $3, $4, $5, $6, 'pending')
+           RETURNING *"#,
+    )
+    .bind(contract.job_id)
+    .bind(contract.id)
+    .bind(milestone.id)
+    .bind(contract.agent_id)
+    .bind(&body.content)
+    .bind(&body.artifacts_url)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    requirement_checks::record(conn, submission.id, &checks).await?;
+
+    states::set_milestone_state(conn, milestone, MilestoneState::Submitted).await?;
+
+    Ok(submission)
+}
+
+/// Whether a contract is paid per milestone rather than through a single escrow.
+pub async fn is_milestone_contract(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<bool> {
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM milestones WHERE contract_id = $1")
+        .bind(contract_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(count.0 > 0)
+}
+
+/// Lock an active contract and one of its milestones.
+pub async fn load_milestone(
+    conn: &mut PgConnection,
+    contract_id: Uuid,
+    milestone_id: Uuid,
+) -> AppResult<(Contract, Milestone)> {
+    let contract = escrow::lock_contract(conn, contract_id).await?;
+
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest("Contract is not active".into()));
+    }
+
+    let milestone = sqlx::query_as::<_, Milestone>(
+        "SELECT * FROM milestones WHERE id = $1 AND contract_id = $2 FOR UPDATE"
+    )
+    .bind(milestone_id)
+    .bind(contract_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Milestone not found".into()))?;
+
+    Ok((contract, milestone))
+}
@@ -1,12 +1,25 @@
 pub mod admin;
+pub mod agent_keys;
 pub mod agents;
 pub mod auth;
 pub mod battle;
+pub mod benchmarks;
+pub mod contracts;
+pub mod dispatches;
+pub mod disputes;
 pub mod favorites;
+pub mod invites;
 pub mod jobs;
+pub mod judging;
 pub mod leaderboard;
+pub mod milestones;
+pub mod negotiation;
+pub mod notifications;
 pub mod offers;
 pub mod reviews;
+pub mod runs;
+pub mod templates;
+pub mod verification;
 pub mod waitlist;
 
 
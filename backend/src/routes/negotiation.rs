@@ -0,0 +1,341 @@
+use axum::{extract::Path, extract::State, Json};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::middleware::{AgentAuth, AgentCaller};
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::offers::MAX_REVISIONS_LIMIT;
+use crate::services::notifications;
+use crate::states::{JobState, OfferStatus};
+use crate::AppState;
+
+/// Cap on counter-proposals per offer, so a negotiation can't run forever.
+const MAX_VERSIONS: i32 = 20;
+
+/// GET /api/offers/:id/revisions — the negotiation thread (job client or agent owner)
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path(offer_id): Path<Uuid>,
+) -> AppResult<Json<Vec<OfferRevision>>> {
+    let mut conn = state.db.acquire().await?;
+    let (offer, job) = offer_and_job(&mut conn, offer_id, false).await?;
+    party_role(&mut conn, &caller, &job, &offer).await?;
+
+    let revisions = sqlx::query_as::<_, OfferRevision>(
+        "SELECT * FROM offer_revisions WHERE offer_id = $1 ORDER BY version ASC"
+    )
+    .bind(offer_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(revisions))
+}
+
+/// POST /api/offers/:id/revisions — counter-propose price, duration and scope.
+/// Fields left out carry over from the latest version; the previous open proposal is superseded.
+pub async fn propose_revision(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path(offer_id): Path<Uuid>,
+    Json(body): Json<ProposeRevisionReq>,
+) -> AppResult<Json<OfferRevision>> {
+    let mut tx = state.db.begin().await?;
+    let (offer, job) = offer_and_job(&mut tx, offer_id, true).await?;
+    let role = party_role(&mut tx, &caller, &job, &offer).await?;
+    check_negotiable(&offer, &job)?;
+
+    let latest = latest_revision(&mut tx, offer_id)
+        .await?
+        .ok_or_else(|| AppError::Internal(format!("Offer {offer_id} has no revisions")))?;
+    let (last_version,): (i32,) = sqlx::query_as("SELECT MAX(version) FROM offer_revisions WHERE offer_id = $1")
+        .bind(offer_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if last_version >= MAX_VERSIONS {
+        return Err(AppError::BadRequest(format!("Negotiation is limited to {MAX_VERSIONS} versions")));
+    }
+
+    let price = body.price_lamports.or(latest.price_lamports);
+    let duration = body.estimated_duration_hours.or(latest.estimated_duration_hours);
+    let max_revisions = body.max_revisions.unwrap_or(latest.max_revisions);
+    let scope = body.scope.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).or(latest.scope.clone());
+
+    if price.is_some_and(|p| p <= 0) {
+        return Err(AppError::BadRequest("price_lamports must be positive".into()));
+    }
+    if duration.is_some_and(|h| h <= 0) {
+        return Err(AppError::BadRequest("estimated_duration_hours must be positive".into()));
+    }
+    if !(0..=MAX_REVISIONS_LIMIT).contains(&max_revisions) {
+        return Err(AppError::BadRequest(format!(
+            "max_revisions must be between 0 and {MAX_REVISIONS_LIMIT}"
+        )));
+    }
+    if price == latest.price_lamports
+        && duration == latest.estimated_duration_hours
+        && max_revisions == latest.max_revisions
+        && scope == latest.scope
+    {
+        return Err(AppError::BadRequest("The proposal doesn't change any terms".into()));
+    }
+
+    // Milestone amounts are fixed to the offer's price
+    if price != latest.price_lamports {
+        let (milestones,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM milestones WHERE offer_id = $1")
+            .bind(offer_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if milestones > 0 {
+            return Err(AppError::BadRequest(
+                "The price of a milestone offer can't be renegotiated; withdraw and re-offer instead".into(),
+            ));
+        }
+    }
+
+    sqlx::query("UPDATE offer_revisions SET status = 'superseded' WHERE offer_id = $1 AND status = 'proposed'")
+        .bind(offer_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let revision = sqlx::query_as::<_, OfferRevision>(
+        r#"INSERT INTO offer_revisions (offer_id, version, proposed_by, proposer_role, price_lamports,
+           estimated_duration_hours, max_revisions, scope, message)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+           RETURNING *"#,
+    )
+    .bind(offer_id)
+    .bind(last_version + 1)
+    .bind(caller.user_id)
+    .bind(role)
+    .bind(price)
+    .bind(duration)
+    .bind(max_revisions)
+    .bind(&scope)
+    .bind(&body.message)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let counterparty = counterparty(&mut tx, role, &job, &offer).await?;
+    tx.commit().await?;
+
+    notifications::notify(
+        &state.db,
+        counterparty,
+        "offer_countered",
+        &format!("New terms (v{}) proposed on an offer for \"{}\"", revision.version, job.title),
+        serde_json::json!({"offer_id": offer_id, "job_id": job.id, "version": revision.version}),
+    )
+    .await;
+
+    Ok(Json(revision))
+}
+
+/// POST /api/offers/:id/revisions/:version/agree — agree to the other side's latest proposal.
+/// The agreed terms become the offer's terms and, once accepted, the contract's.
+pub async fn agree_to_revision(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path((offer_id, version)): Path<(Uuid, i32)>,
+) -> AppResult<Json<Offer>> {
+    let mut tx = state.db.begin().await?;
+    let (offer, job) = offer_and_job(&mut tx, offer_id, true).await?;
+    let role = party_role(&mut tx, &caller, &job, &offer).await?;
+    check_negotiable(&offer, &job)?;
+
+    let latest = latest_revision(&mut tx, offer_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Revision not found".into()))?;
+    if latest.version != version {
+        return Err(AppError::Conflict(format!("Version {version} is not the latest (v{})", latest.version)));
+    }
+    if latest.status != "proposed" {
+        return Err(AppError::Conflict(format!("Version {version} is already agreed")));
+    }
+    if latest.proposer_role == role {
+        return Err(AppError::BadRequest("You can't agree to your own proposal".into()));
+    }
+
+    let updated = agree_revision(&mut tx, &latest, caller.user_id).await?;
+    tx.commit().await?;
+
+    notifications::notify(
+        &state.db,
+        latest.proposed_by,
+        "offer_terms_agreed",
+        &format!("Your terms (v{version}) on an offer for \"{}\" were agreed", job.title),
+        serde_json::json!({"offer_id": offer_id, "job_id": job.id, "version": version}),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/offers/:id/revisions/:version/withdraw — the client takes back their open
+/// counter-proposal; the agent's latest proposal is open again and can be accepted.
+pub async fn withdraw_revision(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path((offer_id, version)): Path<(Uuid, i32)>,
+) -> AppResult<Json<OfferRevision>> {
+    let mut tx = state.db.begin().await?;
+    let (offer, job) = offer_and_job(&mut tx, offer_id, true).await?;
+    let role = party_role(&mut tx, &caller, &job, &offer).await?;
+    check_negotiable(&offer, &job)?;
+
+    if role != "client" {
+        return Err(AppError::Forbidden("Only the client can withdraw a counter-proposal; withdraw the offer instead".into()));
+    }
+
+    let latest = latest_revision(&mut tx, offer_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Revision not found".into()))?;
+    if latest.version != version {
+        return Err(AppError::Conflict(format!("Version {version} is not the latest (v{})", latest.version)));
+    }
+    if latest.status != "proposed" || latest.proposer_role != "client" {
+        return Err(AppError::BadRequest(format!("Version {version} is not your open counter-proposal")));
+    }
+
+    // The terms in force before it: the last agreed version, or the agent's last proposal
+    let previous = sqlx::query_as::<_, OfferRevision>(
+        r#"SELECT * FROM offer_revisions
+           WHERE offer_id = $1 AND version < $2 AND (status = 'agreed' OR proposer_role = 'agent')
+           ORDER BY version DESC LIMIT 1"#,
+    )
+    .bind(offer_id)
+    .bind(version)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Internal(format!("Offer {offer_id} has no agent proposal")))?;
+
+    sqlx::query("UPDATE offer_revisions SET status = 'superseded' WHERE id = $1")
+        .bind(latest.id)
+        .execute(&mut *tx)
+        .await?;
+    let current = sqlx::query_as::<_, OfferRevision>(
+        "UPDATE offer_revisions SET status = 'proposed' WHERE id = $1 AND status = 'superseded' RETURNING *"
+    )
+    .bind(previous.id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .unwrap_or(previous);
+
+    let counterparty = counterparty(&mut tx, role, &job, &offer).await?;
+    tx.commit().await?;
+
+    notifications::notify(
+        &state.db,
+        counterparty,
+        "offer_counter_withdrawn",
+        &format!(
+            "The client withdrew their terms (v{version}) on your offer for \"{}\"; v{} stands again",
+            job.title, current.version
+        ),
+        serde_json::json!({"offer_id": offer_id, "job_id": job.id, "version": current.version}),
+    )
+    .await;
+
+    Ok(Json(current))
+}
+
+/// Current version in an offer's negotiation thread: the newest one not superseded
+/// (by a later proposal, or withdrawn).
+pub async fn latest_revision(conn: &mut PgConnection, offer_id: Uuid) -> AppResult<Option<OfferRevision>> {
+    Ok(sqlx::query_as::<_, OfferRevision>(
+        "SELECT * FROM offer_revisions WHERE offer_id = $1 AND status != 'superseded' ORDER BY version DESC LIMIT 1"
+    )
+    .bind(offer_id)
+    .fetch_optional(&mut *conn)
+    .await?)
+}
+
+/// Mark a proposal agreed and copy its terms onto the offer.
+pub async fn agree_revision(conn: &mut PgConnection, revision: &OfferRevision, user_id: Uuid) -> AppResult<Offer> {
+    sqlx::query("UPDATE offer_revisions SET status = 'agreed', agreed_by = $1, agreed_at = now() WHERE id = $2")
+        .bind(user_id)
+        .bind(revision.id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(sqlx::query_as::<_, Offer>(
+        r#"UPDATE offers SET proposed_price_lamports = $1, estimated_duration_hours = $2, max_revisions = $3,
+           updated_at = now()
+           WHERE id = $4
+           RETURNING *"#,
+    )
+    .bind(revision.price_lamports)
+    .bind(revision.estimated_duration_hours)
+    .bind(revision.max_revisions)
+    .bind(revision.offer_id)
+    .fetch_one(&mut *conn)
+    .await?)
+}
+
+async fn offer_and_job(conn: &mut PgConnection, offer_id: Uuid, lock: bool) -> AppResult<(Offer, Job)> {
+    let query = if lock {
+        "SELECT * FROM offers WHERE id = $1 FOR UPDATE"
+    } else {
+        "SELECT * FROM offers WHERE id = $1"
+    };
+    let offer = sqlx::query_as::<_, Offer>(query)
+        .bind(offer_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Offer not found".into()))?;
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(offer.job_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok((offer, job))
+}
+
+/// Which side of the negotiation the caller is on: the job's client or the agent's owner.
+async fn party_role(
+    conn: &mut PgConnection,
+    caller: &AgentCaller,
+    job: &Job,
+    offer: &Offer,
+) -> AppResult<&'static str> {
+    if caller.agent_id.is_none() && caller.user_id == job.client_id {
+        return Ok("client");
+    }
+    caller.check_agent(offer.agent_id)?;
+
+    let owns_agent: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM agents WHERE id = $1 AND owner_id = $2")
+        .bind(offer.agent_id)
+        .bind(caller.user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    owns_agent
+        .map(|_| "agent")
+        .ok_or_else(|| AppError::Forbidden("You are not a party to this offer".into()))
+}
+
+async fn counterparty(conn: &mut PgConnection, role: &str, job: &Job, offer: &Offer) -> AppResult<Uuid> {
+    if role == "agent" {
+        return Ok(job.client_id);
+    }
+    let (owner_id,): (Uuid,) = sqlx::query_as("SELECT owner_id FROM agents WHERE id = $1")
+        .bind(offer.agent_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(owner_id)
+}
+
+fn check_negotiable(offer: &Offer, job: &Job) -> AppResult<()> {
+    if job.battle_mode {
+        return Err(AppError::BadRequest("Battle entries can't be negotiated".into()));
+    }
+    if offer.status != OfferStatus::Pending || job.state != JobState::Open {
+        return Err(AppError::BadRequest("Offer is no longer open for negotiation".into()));
+    }
+    if offer.expires_at.is_some_and(|t| t <= chrono::Utc::now()) {
+        return Err(AppError::BadRequest("Offer has expired".into()));
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use axum::{extract::Path, extract::State, Json};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::AppState;
+
+/// GET /api/notifications — your latest notifications, unread first
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> AppResult<Json<Vec<Notification>>> {
+    let notifications = sqlx::query_as::<_, Notification>(
+        r#"SELECT * FROM notifications WHERE user_id = $1
+           ORDER BY (read_at IS NULL) DESC, created_at DESC LIMIT 100"#,
+    )
+    .bind(claims.sub)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(notifications))
+}
+
+/// POST /api/notifications/:id/read — mark a notification as read
+pub async fn mark_read(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Notification>> {
+    let updated = sqlx::query_as::<_, Notification>(
+        r#"UPDATE notifications SET read_at = COALESCE(read_at, now())
+           WHERE id = $1 AND user_id = $2 RETURNING *"#,
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Notification not found".into()))?;
+
+    Ok(Json(updated))
+}
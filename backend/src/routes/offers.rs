@@ -1,23 +1,37 @@
 use axum::{extract::Path, extract::State, Json};
+use sqlx::PgConnection;
 use uuid::Uuid;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::{AgentAuth, AuthUser, OptionalAuth};
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::routes::{invites, negotiation};
+use crate::services::{escrow, notifications};
+use crate::states::{self, JobState, OfferStatus};
 use crate::AppState;
 
+/// Revision rounds included in an offer when the agent doesn't specify.
+const DEFAULT_MAX_REVISIONS: i32 = 2;
+/// Upper bound an agent may promise up front; more rounds need an amendment.
+pub(crate) const MAX_REVISIONS_LIMIT: i32 = 10;
+const MAX_MILESTONES: usize = 20;
+/// Longest validity period an offer may carry (90 days).
+const MAX_VALIDITY_HOURS: i32 = 24 * 90;
+
 /// POST /api/offers — submit an offer on a job
 pub async fn create_offer(
     State(state): State<AppState>,
-    AuthUser(claims): AuthUser,
+    AgentAuth(caller): AgentAuth,
     Json(body): Json<CreateOfferReq>,
 ) -> AppResult<Json<Offer>> {
+    caller.check_agent(body.agent_id)?;
+
     // Verify agent belongs to user
     let agent = sqlx::query_as::<_, Agent>(
         "SELECT * FROM agents WHERE id = $1 AND owner_id = $2"
     )
     .bind(body.agent_id)
-    .bind(claims.sub)
+    .bind(caller.user_id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| AppError::Forbidden("Agent does not belong to you".into()))?;
@@ -33,9 +47,10 @@ pub async fn create_offer(
         .await?
         .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
 
-    if job.state != "open" {
+    if job.state != JobState::Open {
         return Err(AppError::BadRequest("Job is not accepting offers".into()));
     }
+    invites::check_can_bid(&state.db, &job, body.agent_id).await?;
 
     // Check duplicate offer
     let existing = sqlx::query_as::<_, Offer>(
@@ -50,27 +65,108 @@ pub async fn create_offer(
         return Err(AppError::Conflict("You already have a pending offer on this job".into()));
     }
 
+    let max_revisions = body.max_revisions.unwrap_or(DEFAULT_MAX_REVISIONS);
+    if !(0..=MAX_REVISIONS_LIMIT).contains(&max_revisions) {
+        return Err(AppError::BadRequest(format!(
+            "max_revisions must be between 0 and {MAX_REVISIONS_LIMIT}"
+        )));
+    }
+
+    if body.valid_for_hours.is_some_and(|h| !(1..=MAX_VALIDITY_HOURS).contains(&h)) {
+        return Err(AppError::BadRequest(format!(
+            "valid_for_hours must be between 1 and {MAX_VALIDITY_HOURS}"
+        )));
+    }
+
+    // Milestones must add up to the proposed price (or define it)
+    let milestones = body.milestones.as_deref().unwrap_or_default();
+    let mut proposed_price = body.proposed_price_lamports;
+    if !milestones.is_empty() {
+        if milestones.len() > MAX_MILESTONES {
+            return Err(AppError::BadRequest(format!("At most {MAX_MILESTONES} milestones per offer")));
+        }
+        if milestones.iter().any(|m| m.title.trim().is_empty() || m.amount_lamports <= 0) {
+            return Err(AppError::BadRequest("Each milestone needs a title and a positive amount".into()));
+        }
+        let total: i64 = milestones.iter().map(|m| m.amount_lamports).sum();
+        match proposed_price {
+            Some(price) if price != total => {
+                return Err(AppError::BadRequest(format!(
+                    "Milestone amounts ({total}) must add up to the proposed price ({price})"
+                )));
+            }
+            _ => proposed_price = Some(total),
+        }
+    }
+
+    // Offer, its first revision and its milestones land together or not at all
+    let mut tx = state.db.begin().await?;
     let offer = sqlx::query_as::<_, Offer>(
-        r#"INSERT INTO offers (job_id, agent_id, proposed_price_lamports, estimated_duration_hours, pitch)
-           VALUES ($1, $2, $3, $4, $5)
+        r#"INSERT INTO offers (job_id, agent_id, proposed_price_lamports, estimated_duration_hours, pitch, max_revisions,
+           expires_at)
+           VALUES ($1, $2, $3, $4, $5, $6, now() + make_interval(hours => $7))
            RETURNING *"#,
     )
     .bind(body.job_id)
     .bind(body.agent_id)
-    .bind(body.proposed_price_lamports)
+    .bind(proposed_price)
     .bind(body.estimated_duration_hours)
     .bind(&body.pitch)
-    .fetch_one(&state.db)
+    .bind(max_revisions)
+    .bind(body.valid_for_hours)
+    .fetch_one(&mut *tx)
     .await?;
 
+    // Version 1 of the negotiation thread is the offer as submitted; battle entries aren't negotiated
+    if !job.battle_mode {
+        sqlx::query(
+            r#"INSERT INTO offer_revisions (offer_id, version, proposed_by, proposer_role, price_lamports,
+               estimated_duration_hours, max_revisions)
+               VALUES ($1, 1, $2, 'agent', $3, $4, $5)"#,
+        )
+        .bind(offer.id)
+        .bind(caller.user_id)
+        .bind(offer.proposed_price_lamports)
+        .bind(offer.estimated_duration_hours)
+        .bind(offer.max_revisions)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (i, m) in milestones.iter().enumerate() {
+        sqlx::query(
+            r#"INSERT INTO milestones (offer_id, position, title, amount_lamports, due_at)
+               VALUES ($1, $2, $3, $4, $5)"#,
+        )
+        .bind(offer.id)
+        .bind(i as i32 + 1)
+        .bind(m.title.trim())
+        .bind(m.amount_lamports)
+        .bind(m.due_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
     Ok(Json(offer))
 }
 
 /// GET /api/offers/job/:job_id — list offers for a job
 pub async fn list_job_offers(
     State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
     Path(job_id): Path<Uuid>,
 ) -> AppResult<Json<Vec<Offer>>> {
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+    if !invites::can_view(&state.db, &job, claims.map(|c| c.sub)).await? {
+        return Err(AppError::NotFound("Job not found".into()));
+    }
+
     let offers = sqlx::query_as::<_, Offer>(
         "SELECT * FROM offers WHERE job_id = $1 ORDER BY created_at ASC"
     )
@@ -81,6 +177,31 @@ pub async fn list_job_offers(
     Ok(Json(offers))
 }
 
+/// GET /api/offers/:id/milestones — proposed payment milestones for an offer
+pub async fn list_offer_milestones(
+    State(state): State<AppState>,
+    OptionalAuth(claims): OptionalAuth,
+    Path(offer_id): Path<Uuid>,
+) -> AppResult<Json<Vec<Milestone>>> {
+    let job = sqlx::query_as::<_, Job>("SELECT j.* FROM jobs j JOIN offers o ON o.job_id = j.id WHERE o.id = $1")
+        .bind(offer_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Offer not found".into()))?;
+    if !invites::can_view(&state.db, &job, claims.map(|c| c.sub)).await? {
+        return Err(AppError::NotFound("Offer not found".into()));
+    }
+
+    let milestones = sqlx::query_as::<_, Milestone>(
+        "SELECT * FROM milestones WHERE offer_id = $1 ORDER BY position ASC"
+    )
+    .bind(offer_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(milestones))
+}
+
 /// POST /api/offers/:id/accept — accept an offer, create contract + escrow
 pub async fn accept_offer(
     State(state): State<AppState>,
@@ -93,43 +214,60 @@ pub async fn accept_offer(
         .await?
         .ok_or_else(|| AppError::NotFound("Offer not found".into()))?;
 
+    // One transaction; the job row lock serializes concurrent accepts on the same job
+    let mut tx = state.db.begin().await?;
+
     // Verify caller owns the job
     let job = sqlx::query_as::<_, Job>(
-        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2"
+        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE"
     )
     .bind(offer.job_id)
     .bind(claims.sub)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await?
     .ok_or_else(|| AppError::Forbidden("Not your job".into()))?;
 
-    if job.state != "open" {
+    if job.state != JobState::Open {
         return Err(AppError::BadRequest("Job is not in open state".into()));
     }
 
-    // Accept the offer, reject others
-    sqlx::query("UPDATE offers SET status = 'accepted', updated_at = now() WHERE id = $1")
+    // Re-read under lock: the offer may have been withdrawn meanwhile
+    let offer = sqlx::query_as::<_, Offer>("SELECT * FROM offers WHERE id = $1 FOR UPDATE")
         .bind(offer_id)
-        .execute(&state.db)
+        .fetch_one(&mut *tx)
         .await?;
 
-    sqlx::query("UPDATE offers SET status = 'rejected', updated_at = now() WHERE job_id = $1 AND id != $2 AND status = 'pending'")
-        .bind(offer.job_id)
-        .bind(offer_id)
-        .execute(&state.db)
-        .await?;
+    // The expiry worker may not have run yet
+    if offer.status == OfferStatus::Pending && offer.expires_at.is_some_and(|t| t <= chrono::Utc::now()) {
+        return Err(AppError::BadRequest("Offer has expired".into()));
+    }
+
+    // Contract terms are the latest mutually agreed version; accepting agrees to the agent's open proposal
+    let (offer, terms) = match negotiation::latest_revision(&mut tx, offer_id).await? {
+        Some(rev) if rev.status == "proposed" && rev.proposer_role == "client" => {
+            return Err(AppError::BadRequest(format!(
+                "Your counter-offer (v{}) is waiting for the agent's agreement; withdraw it to accept the agent's terms",
+                rev.version
+            )));
+        }
+        Some(rev) if rev.status == "proposed" => (negotiation::agree_revision(&mut tx, &rev, claims.sub).await?, Some(rev)),
+        agreed => (offer, agreed),
+    };
+
+    // Accept the offer, reject others
+    states::set_offer_status(&mut tx, &offer, OfferStatus::Accepted).await?;
+
+    reject_other_offers(&mut tx, offer.job_id, offer_id).await?;
 
     // Update job state
-    sqlx::query("UPDATE jobs SET state = 'matched', updated_at = now() WHERE id = $1")
-        .bind(offer.job_id)
-        .execute(&state.db)
-        .await?;
+    states::set_job_state(&mut tx, &job, JobState::Matched).await?;
 
     // Create contract
     let price = offer.proposed_price_lamports.unwrap_or(0);
     let contract = sqlx::query_as::<_, Contract>(
-        r#"INSERT INTO contracts (job_id, offer_id, agent_id, client_id, agreed_price_lamports)
-           VALUES ($1, $2, $3, $4, $5)
+        r#"INSERT INTO contracts (job_id, offer_id, agent_id, client_id, agreed_price_lamports, max_revisions,
+           terms_version, scope)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
            RETURNING *"#,
     )
     .bind(offer.job_id)
@@ -137,25 +275,31 @@ pub async fn accept_offer(
     .bind(offer.agent_id)
     .bind(claims.sub)
     .bind(price)
-    .fetch_one(&state.db)
+    .bind(offer.max_revisions)
+    .bind(terms.as_ref().map(|t| t.version))
+    .bind(terms.and_then(|t| t.scope))
+    .fetch_one(&mut *tx)
     .await?;
 
-    // Create escrow account
-    sqlx::query(
-        "INSERT INTO escrow_accounts (contract_id, amount_lamports) VALUES ($1, $2)"
+    // Milestone offers get one escrow tranche per milestone, otherwise a single account
+    let milestones = sqlx::query_as::<_, Milestone>(
+        "UPDATE milestones SET contract_id = $1 WHERE offer_id = $2 RETURNING *"
     )
     .bind(contract.id)
-    .bind(price)
-    .execute(&state.db)
+    .bind(offer_id)
+    .fetch_all(&mut *tx)
     .await?;
 
+    escrow::open_escrow(&mut tx, &contract, &milestones).await?;
+    tx.commit().await?;
+
     Ok(Json(contract))
 }
 
 /// POST /api/offers/:id/withdraw — withdraw your own offer
 pub async fn withdraw_offer(
     State(state): State<AppState>,
-    AuthUser(claims): AuthUser,
+    AgentAuth(caller): AgentAuth,
     Path(offer_id): Path<Uuid>,
 ) -> AppResult<Json<Offer>> {
     let offer = sqlx::query_as::<_, Offer>("SELECT * FROM offers WHERE id = $1")
@@ -164,29 +308,129 @@ pub async fn withdraw_offer(
         .await?
         .ok_or_else(|| AppError::NotFound("Offer not found".into()))?;
 
+    caller.check_agent(offer.agent_id)?;
+
     // Verify agent belongs to user
     let _agent = sqlx::query_as::<_, Agent>(
         "SELECT * FROM agents WHERE id = $1 AND owner_id = $2"
     )
     .bind(offer.agent_id)
-    .bind(claims.sub)
+    .bind(caller.user_id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| AppError::Forbidden("Not your offer".into()))?;
 
-    if offer.status != "pending" {
-        return Err(AppError::BadRequest("Can only withdraw pending offers".into()));
+    // Conditional update: fails cleanly if the client accepts it concurrently
+    let mut conn = state.db.acquire().await?;
+    let updated = states::set_offer_status(&mut conn, &offer, OfferStatus::Withdrawn).await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/jobs/:id/hire — hire an agent directly at a fixed price, skipping bidding.
+/// Publishes a draft job on the way; other pending offers are rejected.
+pub async fn hire_agent(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(job_id): Path<Uuid>,
+    Json(body): Json<HireAgentReq>,
+) -> AppResult<Json<Contract>> {
+    let max_revisions = body.max_revisions.unwrap_or(DEFAULT_MAX_REVISIONS);
+    if body.price_lamports <= 0 {
+        return Err(AppError::BadRequest("price_lamports must be positive".into()));
+    }
+    if body.estimated_duration_hours.is_some_and(|h| h <= 0) {
+        return Err(AppError::BadRequest("estimated_duration_hours must be positive".into()));
     }
+    if !(0..=MAX_REVISIONS_LIMIT).contains(&max_revisions) {
+        return Err(AppError::BadRequest(format!(
+            "max_revisions must be between 0 and {MAX_REVISIONS_LIMIT}"
+        )));
+    }
+
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1 AND status = 'active'")
+        .bind(body.agent_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Agent not found or not active".into()))?;
 
-    let updated = sqlx::query_as::<_, Offer>(
-        "UPDATE offers SET status = 'withdrawn', updated_at = now() WHERE id = $1 RETURNING *"
+    let mut tx = state.db.begin().await?;
+    let mut job = sqlx::query_as::<_, Job>(
+        "SELECT * FROM jobs WHERE id = $1 AND client_id = $2 FOR UPDATE"
     )
-    .bind(offer_id)
-    .fetch_one(&state.db)
+    .bind(job_id)
+    .bind(claims.sub)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Forbidden("Not your job".into()))?;
+
+    if job.battle_mode {
+        return Err(AppError::BadRequest("Battle jobs can't hire directly".into()));
+    }
+    if job.state == JobState::Draft {
+        job = states::set_job_state(&mut tx, &job, JobState::Open).await?;
+    }
+    if job.state != JobState::Open {
+        return Err(AppError::BadRequest("Job is not in open state".into()));
+    }
+
+    // The hire is recorded as an accepted offer so contracts keep their offer link
+    let offer = sqlx::query_as::<_, Offer>(
+        r#"INSERT INTO offers (job_id, agent_id, proposed_price_lamports, estimated_duration_hours, max_revisions, status)
+           VALUES ($1, $2, $3, $4, $5, 'accepted')
+           RETURNING *"#,
+    )
+    .bind(job_id)
+    .bind(agent.id)
+    .bind(body.price_lamports)
+    .bind(body.estimated_duration_hours)
+    .bind(max_revisions)
+    .fetch_one(&mut *tx)
     .await?;
 
-    Ok(Json(updated))
-}
+    reject_other_offers(&mut tx, job_id, offer.id).await?;
+    states::set_job_state(&mut tx, &job, JobState::Matched).await?;
 
+    let contract = sqlx::query_as::<_, Contract>(
+        r#"INSERT INTO contracts (job_id, offer_id, agent_id, client_id, agreed_price_lamports, max_revisions)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           RETURNING *"#,
+    )
+    .bind(job_id)
+    .bind(offer.id)
+    .bind(agent.id)
+    .bind(claims.sub)
+    .bind(body.price_lamports)
+    .bind(max_revisions)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    escrow::open_escrow(&mut tx, &contract, &[]).await?;
+    tx.commit().await?;
 
+    notifications::notify(
+        &state.db,
+        agent.owner_id,
+        "direct_hire",
+        &format!("Your agent {} was hired directly for \"{}\"", agent.name, job.title),
+        serde_json::json!({"job_id": job_id, "contract_id": contract.id}),
+    )
+    .await;
+
+    Ok(Json(contract))
+}
 
+/// Reject every other pending offer on a job once one is taken.
+async fn reject_other_offers(conn: &mut PgConnection, job_id: Uuid, taken: Uuid) -> AppResult<()> {
+    let others = sqlx::query_as::<_, Offer>(
+        "SELECT * FROM offers WHERE job_id = $1 AND id != $2 AND status = 'pending' FOR UPDATE"
+    )
+    .bind(job_id)
+    .bind(taken)
+    .fetch_all(&mut *conn)
+    .await?;
+    for other in &others {
+        states::set_offer_status(conn, other, OfferStatus::Rejected).await?;
+    }
+    Ok(())
+}
@@ -4,6 +4,7 @@ use uuid::Uuid;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::states::{ContractStatus, EscrowState};
 use crate::services::antifraud;
 use crate::AppState;
 
@@ -32,7 +33,7 @@ pub async fn create_review(
     .await?
     .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
 
-    if contract.status != "completed" {
+    if contract.status != ContractStatus::Completed {
         return Err(AppError::BadRequest("Can only review completed contracts".into()));
     }
 
@@ -45,7 +46,7 @@ pub async fn create_review(
     .await?;
 
     if let Some(e) = &escrow {
-        if e.state == "none" {
+        if e.state == EscrowState::None {
             return Err(AppError::BadRequest("Cannot review: escrow was never funded".into()));
         }
     }
@@ -0,0 +1,219 @@
+use axum::{extract::Path, extract::State, Json};
+use uuid::Uuid;
+
+use crate::auth::middleware::{AgentAuth, AgentCaller, AuthUser};
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::contracts::party_role;
+use crate::services::notifications;
+use crate::states::{ContractStatus, JobState};
+use crate::AppState;
+
+const MAX_EVENT_LEN: usize = 2000;
+
+/// POST /api/contracts/:id/runs — agent starts a run on an active contract (one in flight at a time)
+pub async fn start_run(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<JobRun>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    caller.check_agent(contract.agent_id)?;
+    if party_role(&state, &contract, caller.user_id).await? != "agent" {
+        return Err(AppError::Forbidden("Only the contracted agent can report runs".into()));
+    }
+    if contract.status != ContractStatus::Active {
+        return Err(AppError::BadRequest("Contract is not active".into()));
+    }
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(contract.job_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    if job.state != JobState::InProgress {
+        return Err(AppError::BadRequest(format!(
+            "Cannot start a run while job is '{}'", job.state
+        )));
+    }
+
+    // idx_job_runs_running allows a single running row per contract
+    let run = sqlx::query_as::<_, JobRun>(
+        r#"INSERT INTO job_runs (contract_id, agent_id)
+           VALUES ($1, $2)
+           ON CONFLICT (contract_id) WHERE status = 'running' DO NOTHING
+           RETURNING *"#,
+    )
+    .bind(contract.id)
+    .bind(contract.agent_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::Conflict("A run is already in progress; finish it first".into()))?;
+
+    notifications::notify(
+        &state.db,
+        contract.client_id,
+        "run_started",
+        &format!("The agent started working on \"{}\"", job.title),
+        serde_json::json!({"contract_id": contract.id, "run_id": run.id}),
+    )
+    .await;
+
+    Ok(Json(run))
+}
+
+/// GET /api/contracts/:id/runs — run timeline with progress events, oldest first (client or agent owner)
+pub async fn list_runs(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<JobRunView>>> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+
+    party_role(&state, &contract, claims.sub).await?;
+
+    let runs = sqlx::query_as::<_, JobRun>(
+        "SELECT * FROM job_runs WHERE contract_id = $1 ORDER BY started_at ASC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut timeline = Vec::with_capacity(runs.len());
+    for run in runs {
+        let events = sqlx::query_as::<_, JobRunEvent>(
+            "SELECT * FROM job_run_events WHERE run_id = $1 ORDER BY created_at ASC"
+        )
+        .bind(run.id)
+        .fetch_all(&state.db)
+        .await?;
+
+        timeline.push(JobRunView { run, events });
+    }
+
+    Ok(Json(timeline))
+}
+
+/// POST /api/runs/:id/progress — append a progress update to a running run
+pub async fn report_progress(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path(id): Path<Uuid>,
+    Json(body): Json<RunProgressReq>,
+) -> AppResult<Json<JobRunEvent>> {
+    let message = body.message.trim();
+    if message.is_empty() || message.len() > MAX_EVENT_LEN {
+        return Err(AppError::BadRequest(format!(
+            "Progress message must be 1-{MAX_EVENT_LEN} characters"
+        )));
+    }
+    if let Some(pct) = body.progress_pct {
+        if !(0..=100).contains(&pct) {
+            return Err(AppError::BadRequest("progress_pct must be between 0 and 100".into()));
+        }
+    }
+
+    let run = running_run_for_agent(&state, id, caller).await?;
+
+    let event = sqlx::query_as::<_, JobRunEvent>(
+        r#"INSERT INTO job_run_events (run_id, message, progress_pct)
+           VALUES ($1, $2, $3)
+           RETURNING *"#,
+    )
+    .bind(run.id)
+    .bind(message)
+    .bind(body.progress_pct)
+    .fetch_one(&state.db)
+    .await?;
+
+    if body.progress_pct.is_some() {
+        sqlx::query("UPDATE job_runs SET progress_pct = $1 WHERE id = $2")
+            .bind(body.progress_pct)
+            .bind(run.id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(Json(event))
+}
+
+/// POST /api/runs/:id/finish — close a run as completed or failed, with an optional summary and logs link
+pub async fn finish_run(
+    State(state): State<AppState>,
+    AgentAuth(caller): AgentAuth,
+    Path(id): Path<Uuid>,
+    Json(body): Json<FinishRunReq>,
+) -> AppResult<Json<JobRun>> {
+    if !["completed", "failed"].contains(&body.status.as_str()) {
+        return Err(AppError::BadRequest("status must be 'completed' or 'failed'".into()));
+    }
+    if let Some(url) = &body.logs_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(AppError::BadRequest("logs_url must be an http(s) URL".into()));
+        }
+    }
+
+    let run = running_run_for_agent(&state, id, caller).await?;
+
+    let updated = sqlx::query_as::<_, JobRun>(
+        r#"UPDATE job_runs
+           SET status = $1, output_summary = $2, logs_url = $3, finished_at = now(),
+               progress_pct = CASE WHEN $1 = 'completed' THEN 100 ELSE progress_pct END
+           WHERE id = $4 AND status = 'running'
+           RETURNING *"#,
+    )
+    .bind(&body.status)
+    .bind(&body.output_summary)
+    .bind(&body.logs_url)
+    .bind(run.id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Run was already finished".into()))?;
+
+    let client_id: (Uuid,) = sqlx::query_as("SELECT client_id FROM contracts WHERE id = $1")
+        .bind(run.contract_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    notifications::notify(
+        &state.db,
+        client_id.0,
+        "run_finished",
+        &format!("An agent run on your contract finished: {}", updated.status),
+        serde_json::json!({"contract_id": run.contract_id, "run_id": run.id}),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Load a still-running run of an agent the caller may act for.
+async fn running_run_for_agent(state: &AppState, run_id: Uuid, caller: AgentCaller) -> AppResult<JobRun> {
+    let run = sqlx::query_as::<_, JobRun>(
+        r#"SELECT r.* FROM job_runs r
+           JOIN agents a ON r.agent_id = a.id
+           WHERE r.id = $1 AND a.owner_id = $2"#,
+    )
+    .bind(run_id)
+    .bind(caller.user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Run not found".into()))?;
+
+    caller.check_agent(run.agent_id)?;
+
+    if run.status != "running" {
+        return Err(AppError::BadRequest(format!("Run is already {}", run.status)));
+    }
+
+    Ok(run)
+}
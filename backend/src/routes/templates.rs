@@ -0,0 +1,223 @@
+use axum::{extract::Path, extract::State, Json};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::services::job_templates;
+use crate::AppState;
+
+const MAX_TEMPLATES: i64 = 50;
+const MAX_ACTIVE_SCHEDULES: i64 = 20;
+
+/// POST /api/job-templates — save a reusable job template
+pub async fn create_template(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(body): Json<CreateJobTemplateReq>,
+) -> AppResult<Json<JobTemplate>> {
+    if body.name.trim().is_empty() {
+        return Err(AppError::BadRequest("name is required".into()));
+    }
+    if body.deadline_hours.is_some_and(|h| h <= 0) || body.battle_duration_hours.is_some_and(|h| h <= 0) {
+        return Err(AppError::BadRequest("deadline_hours and battle_duration_hours must be positive".into()));
+    }
+
+    // Same rules as a job created from it right now
+    let now = Utc::now();
+    let job = CreateJobReq {
+        title: body.title,
+        description: body.description,
+        budget_lamports: body.budget_lamports,
+        currency: body.currency,
+        currency_chain: body.currency_chain,
+        battle_mode: body.battle_mode,
+        battle_max_submissions: body.battle_max_submissions,
+        battle_partial_reward_pct: body.battle_partial_reward_pct,
+        battle_closes_at: body.battle_duration_hours.map(|h| now + Duration::hours(h.into())),
+        deadline: body.deadline_hours.map(|h| now + Duration::hours(h.into())),
+        tags: body.tags,
+        visibility: body.visibility,
+        requirements: body.requirements,
+    };
+    job_templates::validate_job_req(&job)?;
+
+    let mut tx = state.db.begin().await?;
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM job_templates WHERE owner_id = $1")
+        .bind(claims.sub)
+        .fetch_one(&mut *tx)
+        .await?;
+    if count >= MAX_TEMPLATES {
+        return Err(AppError::BadRequest(format!("At most {MAX_TEMPLATES} templates per user")));
+    }
+
+    let requirements = serde_json::to_value(job.requirements.unwrap_or_default())
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let template = sqlx::query_as::<_, JobTemplate>(
+        r#"INSERT INTO job_templates (owner_id, name, title, description, budget_lamports, currency, currency_chain,
+           battle_mode, battle_max_submissions, battle_partial_reward_pct, battle_duration_hours, deadline_hours,
+           tags, visibility, requirements)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+           RETURNING *"#,
+    )
+    .bind(claims.sub)
+    .bind(body.name.trim())
+    .bind(&job.title)
+    .bind(&job.description)
+    .bind(job.budget_lamports)
+    .bind(job.currency.as_deref().unwrap_or("USDC"))
+    .bind(job.currency_chain.as_deref().unwrap_or("solana"))
+    .bind(job.battle_mode.unwrap_or(false))
+    .bind(job.battle_max_submissions)
+    .bind(job.battle_partial_reward_pct)
+    .bind(body.battle_duration_hours)
+    .bind(body.deadline_hours)
+    .bind(job.tags.unwrap_or_default())
+    .bind(job.visibility.as_deref().unwrap_or("public"))
+    .bind(requirements)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(template))
+}
+
+/// GET /api/job-templates — your templates
+pub async fn list_templates(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> AppResult<Json<Vec<JobTemplate>>> {
+    let templates = sqlx::query_as::<_, JobTemplate>(
+        "SELECT * FROM job_templates WHERE owner_id = $1 ORDER BY created_at DESC"
+    )
+    .bind(claims.sub)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(templates))
+}
+
+/// GET /api/job-templates/:id
+pub async fn get_template(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<JobTemplate>> {
+    Ok(Json(owned_template(&state, id, claims.sub).await?))
+}
+
+/// DELETE /api/job-templates/:id — delete a template and its schedules
+pub async fn delete_template(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    let result = sqlx::query("DELETE FROM job_templates WHERE id = $1 AND owner_id = $2")
+        .bind(id)
+        .bind(claims.sub)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Template not found".into()));
+    }
+
+    Ok(Json(serde_json::json!({"deleted": true})))
+}
+
+/// POST /api/job-templates/:id/jobs — create a draft job from a template
+pub async fn create_job_from_template(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Job>> {
+    let template = owned_template(&state, id, claims.sub).await?;
+
+    let mut tx = state.db.begin().await?;
+    let req = job_templates::job_req(&template, Utc::now())?;
+    let job = job_templates::insert_job(&mut tx, claims.sub, &req).await?;
+    tx.commit().await?;
+
+    Ok(Json(job))
+}
+
+/// POST /api/job-templates/:id/schedules — post a job from the template on a cron schedule
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<CreateScheduleReq>,
+) -> AppResult<Json<JobSchedule>> {
+    let template = owned_template(&state, id, claims.sub).await?;
+    let cron = body.cron.trim();
+    let next_run = job_templates::first_run(cron, Utc::now())
+        .map_err(|e| AppError::BadRequest(format!("Invalid cron: {e}")))?;
+
+    let (active,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM job_schedules WHERE owner_id = $1 AND is_active")
+        .bind(claims.sub)
+        .fetch_one(&state.db)
+        .await?;
+    if active >= MAX_ACTIVE_SCHEDULES {
+        return Err(AppError::BadRequest(format!("At most {MAX_ACTIVE_SCHEDULES} active schedules per user")));
+    }
+
+    let schedule = sqlx::query_as::<_, JobSchedule>(
+        r#"INSERT INTO job_schedules (template_id, owner_id, cron, auto_publish, next_run_at)
+           VALUES ($1, $2, $3, $4, $5)
+           RETURNING *"#,
+    )
+    .bind(template.id)
+    .bind(claims.sub)
+    .bind(cron)
+    .bind(body.auto_publish.unwrap_or(false))
+    .bind(next_run)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(schedule))
+}
+
+/// GET /api/job-schedules — your recurring job schedules
+pub async fn list_schedules(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> AppResult<Json<Vec<JobSchedule>>> {
+    let schedules = sqlx::query_as::<_, JobSchedule>(
+        "SELECT * FROM job_schedules WHERE owner_id = $1 ORDER BY created_at DESC"
+    )
+    .bind(claims.sub)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(schedules))
+}
+
+/// POST /api/job-schedules/:id/deactivate — stop a recurring schedule
+pub async fn deactivate_schedule(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<JobSchedule>> {
+    let schedule = sqlx::query_as::<_, JobSchedule>(
+        r#"UPDATE job_schedules SET is_active = false, next_run_at = NULL
+           WHERE id = $1 AND owner_id = $2
+           RETURNING *"#,
+    )
+    .bind(id)
+    .bind(claims.sub)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Schedule not found".into()))?;
+
+    Ok(Json(schedule))
+}
+
+async fn owned_template(state: &AppState, id: Uuid, user_id: Uuid) -> AppResult<JobTemplate> {
+    sqlx::query_as::<_, JobTemplate>("SELECT * FROM job_templates WHERE id = $1 AND owner_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Template not found".into()))
+}
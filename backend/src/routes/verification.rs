@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use axum::{extract::Path, extract::State, Json};
+use chrono::Utc;
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthUser;
+use crate::auth::wallet;
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::routes::agents::owned_agent;
+use crate::services::outbound;
+use crate::services::verification::{self, CHALLENGE_TTL_HOURS};
+use crate::AppState;
+
+/// POST /api/agents/:id/verification — issue an ownership challenge (owner only).
+/// Supersedes any pending challenge for the agent.
+pub async fn request_verification(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<RequestVerificationReq>,
+) -> AppResult<Json<VerificationChallengeView>> {
+    let agent = owned_agent(&state, id, claims.sub).await?;
+
+    let well_known_url = match body.method.as_str() {
+        "endpoint" => {
+            let endpoint_url = agent
+                .endpoint_url
+                .as_deref()
+                .filter(|u| !u.trim().is_empty())
+                .ok_or_else(|| AppError::BadRequest("Agent has no endpoint_url to verify".into()))?;
+            Some(verification::well_known_url(endpoint_url))
+        }
+        "wallet" => None,
+        _ => return Err(AppError::BadRequest("method must be 'endpoint' or 'wallet'".into())),
+    };
+
+    sqlx::query("UPDATE agent_verifications SET status = 'expired' WHERE agent_id = $1 AND status = 'pending'")
+        .bind(id)
+        .execute(&state.db)
+        .await?;
+
+    let token = hex::encode(rand::thread_rng().gen::<[u8; 16]>());
+    let challenge = sqlx::query_as::<_, AgentVerification>(
+        r#"INSERT INTO agent_verifications (agent_id, method, token, expires_at)
+           VALUES ($1, $2, $3, $4)
+           RETURNING *"#,
+    )
+    .bind(id)
+    .bind(&body.method)
+    .bind(&token)
+    .bind(Utc::now() + chrono::Duration::hours(CHALLENGE_TTL_HOURS))
+    .fetch_one(&state.db)
+    .await?;
+
+    let message = (body.method == "wallet")
+        .then(|| wallet::build_agent_verification_message(&state.config.domain, id, &token));
+
+    Ok(Json(VerificationChallengeView { verification: challenge, well_known_url, message }))
+}
+
+/// POST /api/agents/:id/verification/check — complete the pending challenge (owner only).
+/// On success the agent becomes `verified` for 90 days.
+pub async fn check_verification(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<CheckVerificationReq>,
+) -> AppResult<Json<AgentVerification>> {
+    let agent = owned_agent(&state, id, claims.sub).await?;
+
+    let challenge = sqlx::query_as::<_, AgentVerification>(
+        r#"SELECT * FROM agent_verifications
+           WHERE agent_id = $1 AND status = 'pending' AND expires_at > now()
+           ORDER BY created_at DESC LIMIT 1"#,
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("No pending verification; request a new one".into()))?;
+
+    let result = match challenge.method.as_str() {
+        "endpoint" => {
+            let endpoint_url = agent
+                .endpoint_url
+                .as_deref()
+                .ok_or_else(|| AppError::BadRequest("Agent has no endpoint_url to verify".into()))?;
+            let http = outbound::client(Duration::from_secs(10)).map_err(|e| AppError::Internal(e.to_string()))?;
+            verification::check_endpoint(&http, endpoint_url, &challenge.token).await
+        }
+        _ => check_wallet(&state, &challenge, claims.sub, &body).await?,
+    };
+
+    if let Err(reason) = result {
+        sqlx::query("UPDATE agent_verifications SET last_error = $1 WHERE id = $2")
+            .bind(&reason)
+            .bind(challenge.id)
+            .execute(&state.db)
+            .await?;
+        return Err(AppError::BadRequest(format!("Verification failed: {reason}")));
+    }
+
+    // Only a wallet challenge proves the wallet it was signed with
+    let wallet = if challenge.method == "wallet" { body.wallet.as_deref() } else { None };
+    let verified = verification::mark_verified(&state.db, &challenge, wallet).await?;
+
+    sqlx::query(
+        "INSERT INTO audit_logs (user_id, action, entity_type, entity_id, metadata) VALUES ($1, 'agent_verified', 'agent', $2, $3)"
+    )
+    .bind(claims.sub)
+    .bind(id)
+    .bind(serde_json::json!({"method": verified.method, "wallet": verified.wallet}))
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(verified))
+}
+
+/// GET /api/agents/:id/verification — verification history, newest first (owner only)
+pub async fn list_verifications(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<AgentVerification>>> {
+    owned_agent(&state, id, claims.sub).await?;
+
+    let verifications = sqlx::query_as::<_, AgentVerification>(
+        "SELECT * FROM agent_verifications WHERE agent_id = $1 ORDER BY created_at DESC"
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(verifications))
+}
+
+/// The signature must come from one of the owner's own wallets.
+async fn check_wallet(
+    state: &AppState,
+    challenge: &AgentVerification,
+    owner_id: Uuid,
+    body: &CheckVerificationReq,
+) -> AppResult<Result<(), String>> {
+    let (Some(address), Some(signature)) = (&body.wallet, &body.signature) else {
+        return Err(AppError::BadRequest("wallet and signature are required".into()));
+    };
+
+    let wallet_type: (String,) = sqlx::query_as(
+        "SELECT wallet_type FROM wallets WHERE user_id = $1 AND public_key = $2"
+    )
+    .bind(owner_id)
+    .bind(address)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::Forbidden("Wallet is not linked to your account".into()))?;
+
+    let message = wallet::build_agent_verification_message(&state.config.domain, challenge.agent_id, &challenge.token);
+    Ok(wallet::verify_wallet_signature(&wallet_type.0, address, signature, &message).map_err(|e| e.to_string()))
+}
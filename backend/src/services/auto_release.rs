@@ -0,0 +1,194 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::error::AppResult;
+use crate::models::*;
+use crate::services::{escrow, notifications};
+use crate::states::{self, SubmissionStatus};
+
+/// Review-window worker: reminds clients about submitted work awaiting review and
+/// auto-releases escrow that stayed `locked` past the window.
+///
+/// Opening a dispute moves escrow to `frozen` and a revision request unlocks it,
+/// so both stop the clock; the next submission re-locks and restarts it. An
+/// amendment top-up the client never funded is cancelled, not waited for.
+pub async fn run_review_window(db: &PgPool, window_hours: i64, reminder_hours: i64) {
+    let now = Utc::now();
+    let remind_cutoff = now - Duration::hours((window_hours - reminder_hours).max(0));
+    let release_cutoff = now - Duration::hours(window_hours);
+
+    // 1. Reminders
+    let due_soon = sqlx::query_as::<_, EscrowAccount>(
+        r#"SELECT * FROM escrow_accounts
+           WHERE state = 'locked' AND reminder_sent_at IS NULL
+             AND locked_at < $1 AND locked_at >= $2"#,
+    )
+    .bind(remind_cutoff)
+    .bind(release_cutoff)
+    .fetch_all(db)
+    .await;
+    let due_soon = match due_soon {
+        Ok(due_soon) => due_soon,
+        Err(e) => {
+            tracing::warn!("Review reminder scan failed: {e}");
+            return;
+        }
+    };
+
+    for escrow in due_soon {
+        if let Err(e) = send_reminder(db, &escrow, window_hours).await {
+            tracing::warn!(escrow_id = %escrow.id, "Review reminder failed: {e}");
+        }
+    }
+
+    // 2. Auto-release
+    let expired = sqlx::query_as::<_, EscrowAccount>(
+        "SELECT * FROM escrow_accounts WHERE state = 'locked' AND locked_at < $1"
+    )
+    .bind(release_cutoff)
+    .fetch_all(db)
+    .await;
+    let expired = match expired {
+        Ok(expired) => expired,
+        Err(e) => {
+            tracing::warn!("Auto-release scan failed: {e}");
+            return;
+        }
+    };
+
+    let mut released = 0;
+    for escrow in expired {
+        match auto_release(db, &escrow, window_hours).await {
+            Ok(()) => released += 1,
+            Err(e) => tracing::warn!(escrow_id = %escrow.id, "Auto-release failed: {e}"),
+        }
+    }
+
+    if released > 0 {
+        tracing::info!("Auto-released {released} escrow account(s) after the review window");
+    }
+}
+
+async fn send_reminder(db: &PgPool, escrow: &EscrowAccount, window_hours: i64) -> AppResult<()> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(escrow.contract_id)
+        .fetch_one(db)
+        .await?;
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(contract.job_id)
+        .fetch_one(db)
+        .await?;
+
+    let release_at = escrow.locked_at.unwrap_or_else(Utc::now) + Duration::hours(window_hours);
+    let mut message = format!(
+        "Work on \"{}\" is awaiting your review and will be auto-approved on {}",
+        job.title,
+        release_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    if escrow.topup_due_lamports > 0 {
+        message.push_str(&format!(
+            "; the unfunded {} lamport amendment top-up will then be cancelled",
+            escrow.topup_due_lamports
+        ));
+    }
+    notifications::notify(
+        db,
+        contract.client_id,
+        "review_reminder",
+        &message,
+        serde_json::json!({
+            "contract_id": contract.id,
+            "milestone_id": escrow.milestone_id,
+            "release_at": release_at,
+            "topup_due_lamports": escrow.topup_due_lamports,
+        }),
+    )
+    .await;
+
+    sqlx::query("UPDATE escrow_accounts SET reminder_sent_at = now() WHERE id = $1")
+        .bind(escrow.id)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+async fn auto_release(db: &PgPool, escrow: &EscrowAccount, window_hours: i64) -> AppResult<()> {
+    // Same locks as the client's approve path; escrow state is re-checked under them,
+    // so a dispute or approval that landed since the scan wins.
+    let mut tx = db.begin().await?;
+    let contract = escrow::lock_contract(&mut tx, escrow.contract_id).await?;
+    let mut cancelled_topup = 0;
+
+    match escrow.milestone_id {
+        Some(milestone_id) => {
+            escrow::release_milestone(&mut tx, &contract, milestone_id).await?;
+        }
+        None => {
+            let pending = sqlx::query_as::<_, Submission>(
+                "SELECT * FROM submissions WHERE contract_id = $1 AND status = 'pending' FOR UPDATE"
+            )
+            .bind(contract.id)
+            .fetch_all(&mut *tx)
+            .await?;
+            for submission in &pending {
+                states::set_submission_status(&mut tx, submission, SubmissionStatus::Accepted, None).await?;
+            }
+
+            cancelled_topup = escrow::cancel_top_up(&mut tx, &contract).await?;
+            escrow::release_contract_escrow(&mut tx, &contract).await?;
+        }
+    }
+
+    sqlx::query(
+        r#"INSERT INTO audit_logs (user_id, action, entity_type, entity_id, metadata)
+           VALUES (NULL, 'auto_release', 'escrow', $1, $2)"#,
+    )
+    .bind(escrow.id)
+    .bind(serde_json::json!({
+        "contract_id": contract.id,
+        "milestone_id": escrow.milestone_id,
+        "amount_lamports": escrow.amount_lamports - cancelled_topup,
+        "cancelled_topup_lamports": cancelled_topup,
+        "review_window_hours": window_hours,
+        "automatic": true,
+    }))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let owner: (uuid::Uuid,) = sqlx::query_as("SELECT owner_id FROM agents WHERE id = $1")
+        .bind(contract.agent_id)
+        .fetch_one(db)
+        .await?;
+
+    let topup_note = if cancelled_topup > 0 {
+        format!(" The unfunded {cancelled_topup} lamport amendment top-up was cancelled.")
+    } else {
+        String::new()
+    };
+    let metadata = serde_json::json!({
+        "contract_id": contract.id,
+        "milestone_id": escrow.milestone_id,
+        "cancelled_topup_lamports": cancelled_topup,
+    });
+    notifications::notify(
+        db,
+        contract.client_id,
+        "escrow_auto_released",
+        &format!("The review window expired, so escrow was released to the agent automatically.{topup_note}"),
+        metadata.clone(),
+    )
+    .await;
+    notifications::notify(
+        db,
+        owner.0,
+        "escrow_auto_released",
+        &format!("The client did not respond within the review window; your payment was released.{topup_note}"),
+        metadata,
+    )
+    .await;
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Battle rating: a multi-player Elo over decided battles, kept apart from the
+//! Lobster Score. Every entrant plays one virtual match against every other;
+//! placing above an opponent scores 1, sharing a place 0.5, placing below 0.
+
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+
+/// Maximum rating swing per battle.
+const K_FACTOR: f32 = 32.0;
+
+/// Re-rate every entrant of a battle. `ranked` lists the placed submissions, best
+/// first (winner, rewarded runners-up); other entrants follow in rubric order,
+/// and those never scored share last place. Battles with one entrant change nothing.
+pub async fn rate_battle(conn: &mut PgConnection, job_id: Uuid, ranked: &[Uuid]) -> AppResult<()> {
+    // (agent_id, rating, submission_id, rubric_rank)
+    let entrants: Vec<(Uuid, f32, Uuid, Option<i32>)> = sqlx::query_as(
+        r#"SELECT a.id, a.battle_rating, s.id, s.rubric_rank
+           FROM submissions s JOIN agents a ON a.id = s.agent_id
+           WHERE s.job_id = $1 AND s.is_battle_submission = true
+           ORDER BY s.rubric_rank NULLS LAST, s.created_at
+           FOR UPDATE OF a"#,
+    )
+    .bind(job_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let n = entrants.len();
+    if n < 2 {
+        return Ok(());
+    }
+
+    let placements = placements(&entrants, ranked);
+    let ratings: Vec<f32> = entrants.iter().map(|e| e.1).collect();
+    let new_ratings = rerate(&ratings, &placements);
+
+    for (i, (agent_id, before, _, _)) in entrants.iter().enumerate() {
+        sqlx::query(
+            "UPDATE agents SET battle_rating = $1, battles_played = battles_played + 1, updated_at = now() WHERE id = $2"
+        )
+        .bind(new_ratings[i])
+        .bind(agent_id)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"INSERT INTO battle_rating_history (agent_id, job_id, placement, entrants, rating_before, rating_after)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(agent_id)
+        .bind(job_id)
+        .bind(placements[i] as i32)
+        .bind(n as i32)
+        .bind(before)
+        .bind(new_ratings[i])
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Placement of each entrant (1-based). `entrants` is already in rubric order.
+fn placements(entrants: &[(Uuid, f32, Uuid, Option<i32>)], ranked: &[Uuid]) -> Vec<usize> {
+    let mut next = ranked.len() + 1;
+    let mut last = None;
+    entrants
+        .iter()
+        .map(|(_, _, submission_id, rubric_rank)| {
+            if let Some(pos) = ranked.iter().position(|id| id == submission_id) {
+                return pos + 1;
+            }
+            match rubric_rank {
+                Some(_) => {
+                    next += 1;
+                    next - 1
+                }
+                None => *last.get_or_insert(next),
+            }
+        })
+        .collect()
+}
+
+/// New ratings after one battle, from each entrant's rating and placement.
+fn rerate(ratings: &[f32], placements: &[usize]) -> Vec<f32> {
+    let n = ratings.len();
+    let k = K_FACTOR / (n - 1) as f32;
+    (0..n)
+        .map(|i| {
+            let delta: f32 = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let actual = match placements[i].cmp(&placements[j]) {
+                        std::cmp::Ordering::Less => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Greater => 0.0,
+                    };
+                    let expected = 1.0 / (1.0 + 10f32.powf((ratings[j] - ratings[i]) / 400.0));
+                    actual - expected
+                })
+                .sum();
+            ratings[i] + k * delta
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Entrant = (Uuid, f32, Uuid, Option<i32>);
+    /// (rubric ranks, indices of placed submissions best first, expected placements)
+    type PlacementCase = (&'static [Option<i32>], &'static [usize], &'static [usize]);
+
+    /// Entrants in rubric order with the given rubric ranks, and their submission ids.
+    fn entrants(rubric_ranks: &[Option<i32>]) -> (Vec<Entrant>, Vec<Uuid>) {
+        let entrants: Vec<_> = rubric_ranks
+            .iter()
+            .map(|&rank| (Uuid::new_v4(), 1500.0, Uuid::new_v4(), rank))
+            .collect();
+        let submissions = entrants.iter().map(|e| e.2).collect();
+        (entrants, submissions)
+    }
+
+    #[test]
+    fn placements_follow_prizes_then_rubric_and_tie_the_unscored() {
+        let cases: [PlacementCase; 5] = [
+            (&[Some(1), Some(2), Some(3)], &[0], &[1, 2, 3]),
+            // the client picked the rubric's runner-up as winner
+            (&[Some(1), Some(2), Some(3)], &[1, 0], &[2, 1, 3]),
+            (&[Some(1), Some(2), None, None], &[0], &[1, 2, 3, 3]),
+            (&[None, None, None], &[2], &[2, 2, 1]),
+            (&[Some(1), Some(2), Some(3), None], &[2, 0, 1], &[2, 3, 1, 4]),
+        ];
+        for (ranks, placed, expected) in cases {
+            let (entrants, submissions) = entrants(ranks);
+            let ranked: Vec<Uuid> = placed.iter().map(|&i| submissions[i]).collect();
+            assert_eq!(placements(&entrants, &ranked), expected, "{ranks:?} placed {placed:?}");
+        }
+    }
+
+    #[test]
+    fn rerate_scores_every_pairing() {
+        // (ratings, placements, expected ratings)
+        let cases: [(&[f32], &[usize], &[f32]); 5] = [
+            (&[1500.0, 1500.0], &[1, 2], &[1516.0, 1484.0]),
+            (&[1500.0, 1500.0], &[1, 1], &[1500.0, 1500.0]),
+            (&[1500.0, 1500.0, 1500.0], &[1, 2, 2], &[1516.0, 1492.0, 1492.0]),
+            (&[1500.0, 1500.0, 1500.0], &[1, 2, 3], &[1516.0, 1500.0, 1484.0]),
+            // a tie against a weaker entrant costs the favourite
+            (&[1600.0, 1400.0], &[1, 1], &[1591.688, 1408.312]),
+        ];
+        for (ratings, placements, expected) in cases {
+            let got = rerate(ratings, placements);
+            for (g, e) in got.iter().zip(expected) {
+                assert!((g - e).abs() < 0.01, "{ratings:?} placed {placements:?}: got {got:?}");
+            }
+            let (before, after): (f32, f32) = (ratings.iter().sum(), got.iter().sum());
+            assert!((before - after).abs() < 0.01, "rating is only moved between entrants");
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::Instant;
+
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::services::{dispatch, outbound, verification};
+
+/// Weighted run score (0-100) needed to pass a capability.
+pub const PASS_SCORE: f32 = 80.0;
+/// Scoring functions a task may use.
+pub const SCORING_FUNCTIONS: [&str; 4] = ["exact", "contains", "json_subset", "numeric"];
+/// A run still `running` after this long lost its task (restart or crash).
+pub const STALE_RUN_HOURS: i64 = 1;
+
+/// Execute a benchmark run: send each active task for the capability to the agent's
+/// endpoint, score the responses, then finish the run and recompute the agent's tier.
+pub async fn execute(db: PgPool, http: reqwest::Client, run: BenchmarkRun, endpoint_url: String) {
+    if let Err(e) = execute_inner(&db, &http, &run, &endpoint_url).await {
+        tracing::warn!(run_id = %run.id, "Benchmark run failed: {e}");
+        let marked = sqlx::query(
+            "UPDATE benchmark_runs SET status = 'failed', finished_at = now() WHERE id = $1 AND status = 'running'"
+        )
+        .bind(run.id)
+        .execute(&db)
+        .await;
+        if let Err(e) = marked {
+            tracing::warn!(run_id = %run.id, "Recording benchmark run failure failed: {e}");
+        }
+    }
+}
+
+/// Fail runs left `running` since before `now - max_age`; their task is gone, and
+/// `idx_benchmark_runs_running` would otherwise block every later run.
+pub async fn fail_stale_runs(db: &PgPool, max_age: Duration) {
+    let result = sqlx::query(
+        "UPDATE benchmark_runs SET status = 'failed', finished_at = now() WHERE status = 'running' AND started_at < $1"
+    )
+    .bind(Utc::now() - max_age)
+    .execute(db)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => tracing::info!("Failed {} abandoned benchmark run(s)", r.rows_affected()),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failing abandoned benchmark runs failed: {e}"),
+    }
+}
+
+async fn execute_inner(db: &PgPool, http: &reqwest::Client, run: &BenchmarkRun, endpoint_url: &str) -> AppResult<()> {
+    let tasks = sqlx::query_as::<_, BenchmarkTask>(
+        "SELECT * FROM benchmark_tasks WHERE capability = $1 AND is_active ORDER BY created_at"
+    )
+    .bind(&run.capability)
+    .fetch_all(db)
+    .await?;
+
+    let secret = dispatch::agent_secret(db, run.agent_id).await?;
+
+    let (mut weighted, mut total_weight) = (0.0_f32, 0.0_f32);
+    for task in &tasks {
+        let started = Instant::now();
+        let response = send_task(http, endpoint_url, &secret, run, task).await;
+        let latency_ms = started.elapsed().as_millis() as i32;
+
+        let (output, score, error) = match response {
+            Ok(output) => {
+                let score = score_output(task, &output);
+                (Some(output), score, None)
+            }
+            Err(e) => (None, 0.0, Some(e)),
+        };
+
+        weighted += score * task.weight;
+        total_weight += task.weight;
+
+        sqlx::query(
+            r#"INSERT INTO benchmark_results (run_id, task_id, output, score, latency_ms, error)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(run.id)
+        .bind(task.id)
+        .bind(output)
+        .bind(score)
+        .bind(latency_ms)
+        .bind(error)
+        .execute(db)
+        .await?;
+    }
+
+    let score = if total_weight > 0.0 { weighted / total_weight * 100.0 } else { 0.0 };
+    // A run swept as stale stays failed
+    let finished = sqlx::query(
+        r#"UPDATE benchmark_runs
+           SET status = 'completed', score = $1, passed = $2, task_count = $3, finished_at = now()
+           WHERE id = $4 AND status = 'running'"#,
+    )
+    .bind(score)
+    .bind(score >= PASS_SCORE)
+    .bind(tasks.len() as i32)
+    .bind(run.id)
+    .execute(db)
+    .await?;
+
+    if finished.rows_affected() == 0 {
+        return Err(AppError::Conflict("Run was failed as abandoned before it finished".into()));
+    }
+
+    verification::recompute_tier(db, run.agent_id).await?;
+
+    Ok(())
+}
+
+/// POST the task input to the agent, signed like a job dispatch. The agent answers
+/// with `{"output": ...}`; any other JSON body is taken as the output itself.
+async fn send_task(
+    http: &reqwest::Client,
+    endpoint_url: &str,
+    secret: &str,
+    run: &BenchmarkRun,
+    task: &BenchmarkTask,
+) -> Result<Value, String> {
+    let body = serde_json::to_vec(&serde_json::json!({
+        "event": "benchmark.task",
+        "run_id": run.id,
+        "task_id": task.id,
+        "capability": task.capability,
+        "input": task.input,
+    }))
+    .map_err(|e| e.to_string())?;
+
+    outbound::check_url(endpoint_url).await?;
+    let timestamp = Utc::now().timestamp();
+    let resp = http
+        .post(endpoint_url)
+        .header("Content-Type", "application/json")
+        .header("X-Lobster-Timestamp", timestamp.to_string())
+        .header("X-Lobster-Signature", format!("sha256={}", dispatch::sign(secret, timestamp, &body)))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Endpoint responded {}", resp.status()));
+    }
+
+    let mut value: Value = resp.json().await.map_err(|e| format!("Response is not JSON: {e}"))?;
+    Ok(value.get_mut("output").map(Value::take).unwrap_or(value))
+}
+
+/// Score one output against the task's expectation, 0.0-1.0.
+pub fn score_output(task: &BenchmarkTask, output: &Value) -> f32 {
+    let expected = &task.expected_output;
+    match task.scoring.as_str() {
+        "contains" => match (output.as_str(), expected.as_str()) {
+            (Some(out), Some(exp)) => out.to_lowercase().contains(&exp.to_lowercase()) as u8 as f32,
+            _ => 0.0,
+        },
+        // Fraction of expected top-level fields the output reproduces exactly
+        "json_subset" => match (output.as_object(), expected.as_object()) {
+            (Some(out), Some(exp)) if !exp.is_empty() => {
+                let hits = exp.iter().filter(|(k, v)| out.get(*k) == Some(*v)).count();
+                hits as f32 / exp.len() as f32
+            }
+            _ => (output == expected) as u8 as f32,
+        },
+        "numeric" => match (output.as_f64(), expected.as_f64()) {
+            (Some(out), Some(exp)) => ((out - exp).abs() <= task.tolerance.unwrap_or(0.0)) as u8 as f32,
+            _ => 0.0,
+        },
+        _ => (output == expected) as u8 as f32,
+    }
+}
@@ -0,0 +1,227 @@
+//! Minimal cron expressions for recurring jobs: the standard five fields
+//! (minute hour day-of-month month day-of-week, evaluated in UTC) with `*`,
+//! lists, ranges and steps, plus the `@hourly`, `@daily`, `@weekly` and
+//! `@monthly` shorthands.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and day-of-week were both restricted (neither starts with `*`):
+    /// either may match (as in cron)
+    either_day: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err("Expected 5 fields: minute hour day-of-month month day-of-week".into());
+        };
+
+        let mut weekdays = field(weekday, 0, 7, "day-of-week")?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(Self {
+            minutes: field(minute, 0, 59, "minute")?,
+            hours: field(hour, 0, 23, "hour")?,
+            days: field(day, 1, 31, "day-of-month")?,
+            months: field(month, 1, 12, "month")?,
+            weekdays,
+            either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+        })
+    }
+
+    /// First matching minute strictly after `after`, within the next four years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = after + Duration::days(4 * 366);
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        while t < limit {
+            if !has(self.months, t.month()) {
+                let (y, m) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = midnight(NaiveDate::from_ymd_opt(y, m, 1)?);
+                continue;
+            }
+            if !self.day_matches(t) {
+                t = midnight(t.date_naive().succ_opt()?);
+                continue;
+            }
+            if !has(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let dom = has(self.days, t.day());
+        let dow = has(self.weekdays, t.weekday().num_days_from_sunday());
+        if self.either_day { dom || dow } else { dom && dow }
+    }
+}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+}
+
+/// Parse one field into a bitmask of allowed values.
+fn field(spec: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid {name} field '{spec}'");
+    let mut mask = 0u64;
+
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (lo, hi) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((lo, hi)) => (lo.parse().map_err(|_| invalid())?, hi.parse().map_err(|_| invalid())?),
+                // `5/15` runs from 5 to the end of the range
+                None => {
+                    let lo: u32 = range.parse().map_err(|_| invalid())?;
+                    (lo, if part.contains('/') { max } else { lo })
+                }
+            },
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(format!("{name} values must be between {min} and {max}"));
+        }
+        for value in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        CronSchedule::parse(expr).unwrap().next_after(after)
+    }
+
+    // 2026-10-17 is a Saturday
+    const Y: i32 = 2026;
+
+    #[test]
+    fn next_is_strictly_after() {
+        assert_eq!(next("15 10 * * *", at(Y, 10, 17, 10, 15)), Some(at(Y, 10, 18, 10, 15)));
+        let mid_minute = at(Y, 10, 17, 10, 14) + Duration::seconds(30);
+        assert_eq!(next("15 10 * * *", mid_minute), Some(at(Y, 10, 17, 10, 15)));
+    }
+
+    #[test]
+    fn steps_ranges_and_lists() {
+        assert_eq!(next("*/15 * * * *", at(Y, 10, 17, 10, 7)), Some(at(Y, 10, 17, 10, 15)));
+        assert_eq!(next("*/15 * * * *", at(Y, 10, 17, 10, 45)), Some(at(Y, 10, 17, 11, 0)));
+        // `5/20` runs from 5 to the end of the range
+        assert_eq!(next("5/20 * * * *", at(Y, 10, 17, 10, 26)), Some(at(Y, 10, 17, 10, 45)));
+        assert_eq!(next("0 0-12/6 * * *", at(Y, 10, 17, 6, 0)), Some(at(Y, 10, 17, 12, 0)));
+        assert_eq!(next("0 0-12/6 * * *", at(Y, 10, 17, 12, 0)), Some(at(Y, 10, 18, 0, 0)));
+        assert_eq!(next("30 9,17 * * 1-5", at(Y, 10, 17, 8, 0)), Some(at(Y, 10, 19, 9, 30)));
+        assert_eq!(next("30 9,17 * * 1-5", at(Y, 10, 19, 9, 30)), Some(at(Y, 10, 19, 17, 30)));
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // Both restricted: either matches
+        assert_eq!(next("15 10 1 * 1", at(Y, 10, 17, 0, 0)), Some(at(Y, 10, 19, 10, 15)));
+        assert_eq!(next("15 10 1 * 1", at(Y, 10, 26, 10, 15)), Some(at(Y, 11, 1, 10, 15)));
+        // Only one restricted: that one alone decides
+        assert_eq!(next("0 9 * * 1", at(Y, 10, 17, 0, 0)), Some(at(Y, 10, 19, 9, 0)));
+        assert_eq!(next("0 9 1 * *", at(Y, 10, 17, 0, 0)), Some(at(Y, 11, 1, 9, 0)));
+        // A stepped `*` still counts as unrestricted: odd days that are Mondays
+        assert_eq!(next("0 9 */2 * 1", at(Y, 10, 17, 0, 0)), Some(at(Y, 10, 19, 9, 0)));
+        assert_eq!(next("0 9 */2 * 1", at(Y, 10, 19, 10, 0)), Some(at(Y, 11, 9, 9, 0)));
+    }
+
+    #[test]
+    fn seven_is_sunday() {
+        assert_eq!(next("0 0 * * 7", at(Y, 10, 17, 12, 0)), Some(at(Y, 10, 18, 0, 0)));
+        assert_eq!(next("0 0 * * 7", at(Y, 10, 17, 12, 0)), next("0 0 * * 0", at(Y, 10, 17, 12, 0)));
+        assert_eq!(next("0 0 * * 5-7", at(Y, 10, 18, 12, 0)), Some(at(Y, 10, 23, 0, 0)));
+    }
+
+    #[test]
+    fn shorthands() {
+        let now = at(Y, 10, 17, 13, 37);
+        assert_eq!(next("@hourly", now), Some(at(Y, 10, 17, 14, 0)));
+        assert_eq!(next("@daily", now), Some(at(Y, 10, 18, 0, 0)));
+        assert_eq!(next("@weekly", now), Some(at(Y, 10, 18, 0, 0)));
+        assert_eq!(next("@monthly", now), Some(at(Y, 11, 1, 0, 0)));
+        assert_eq!(next("  @daily ", now), Some(at(Y, 10, 18, 0, 0)));
+    }
+
+    #[test]
+    fn rare_and_never_firing() {
+        assert_eq!(next("0 0 29 2 *", at(Y, 10, 17, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+        assert_eq!(next("0 0 31 * *", at(Y, 10, 31, 0, 0)), Some(at(Y, 12, 31, 0, 0)));
+        assert_eq!(next("0 0 31 4 *", at(Y, 10, 17, 0, 0)), None);
+        assert_eq!(next("0 0 30 2 *", at(Y, 10, 17, 0, 0)), None);
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expr in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "@yearly",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * 32 * *",
+            "* * * 0 *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "1,,2 * * * *",
+            "1-2-3 * * * *",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "{expr:?}");
+        }
+        assert_eq!(
+            CronSchedule::parse("* 24 * * *").unwrap_err(),
+            "hour values must be between 0 and 23"
+        );
+        assert_eq!(
+            CronSchedule::parse("* * * *").unwrap_err(),
+            "Expected 5 fields: minute hour day-of-month month day-of-week"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::*;
+use crate::services::notifications;
+use crate::states::{self, JobState, OfferStatus};
+
+/// Deadline enforcement: stamps each contract's due date and delivery time, flags
+/// late contracts and recomputes `agents.on_time_pct` from that history.
+///
+/// A contract is due at the earlier of the job `deadline` and acceptance time +
+/// the offer's `estimated_duration_hours`; contracts with neither are never judged.
+/// Delivery is the agent's first submission — per milestone on milestone contracts,
+/// where the last milestone delivered counts — so client revision rounds don't
+/// count against the agent.
+pub async fn enforce_deadlines(db: &PgPool) {
+    // 1. Due dates for contracts that don't have one yet
+    if let Err(e) = sqlx::query(
+        r#"UPDATE contracts c
+           SET due_at = LEAST(j.deadline, c.created_at + make_interval(hours => o.estimated_duration_hours))
+           FROM jobs j, offers o
+           WHERE c.job_id = j.id AND c.offer_id = o.id AND c.due_at IS NULL
+             AND (j.deadline IS NOT NULL OR o.estimated_duration_hours IS NOT NULL)"#,
+    )
+    .execute(db)
+    .await
+    {
+        tracing::warn!("Stamping contract due dates failed: {e}");
+    }
+
+    // 2. Delivered contracts: every milestone (or the contract itself) has a submission
+    if let Err(e) = sqlx::query(
+        r#"UPDATE contracts c
+           SET delivered_at = d.delivered_at, is_late = COALESCE(c.is_late, false) OR d.delivered_at > c.due_at
+           FROM (
+               SELECT contract_id, MAX(first_at) AS delivered_at
+               FROM (SELECT contract_id, milestone_id, MIN(created_at) AS first_at
+                     FROM submissions WHERE contract_id IS NOT NULL
+                     GROUP BY contract_id, milestone_id) s
+               GROUP BY contract_id
+           ) d
+           WHERE c.id = d.contract_id AND c.due_at IS NOT NULL AND c.delivered_at IS NULL
+             AND NOT EXISTS (
+                 SELECT 1 FROM milestones m
+                 WHERE m.contract_id = c.id
+                   AND NOT EXISTS (SELECT 1 FROM submissions s WHERE s.milestone_id = m.id)
+             )"#,
+    )
+    .execute(db)
+    .await
+    {
+        tracing::warn!("Recording contract deliveries failed: {e}");
+    }
+
+    // 3. Overdue: past due with nothing delivered yet
+    let overdue: Vec<(Uuid, Uuid, Uuid)> = match sqlx::query_as(
+        r#"UPDATE contracts c SET is_late = true
+           FROM agents a
+           WHERE a.id = c.agent_id AND c.status = 'active' AND c.is_late IS NULL
+             AND c.delivered_at IS NULL AND c.due_at < now()
+           RETURNING c.id, c.client_id, a.owner_id"#,
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(overdue) => overdue,
+        Err(e) => {
+            tracing::warn!("Flagging overdue contracts failed: {e}");
+            Vec::new()
+        }
+    };
+
+    for (contract_id, client_id, owner_id) in &overdue {
+        let metadata = serde_json::json!({"contract_id": contract_id});
+        notifications::notify(
+            db,
+            *client_id,
+            "contract_overdue",
+            "A contract you hired for is past its due date without a delivery",
+            metadata.clone(),
+        )
+        .await;
+        notifications::notify(
+            db,
+            *owner_id,
+            "contract_overdue",
+            "Your agent missed the due date on a contract; it now counts as late",
+            metadata,
+        )
+        .await;
+    }
+
+    // 4. Recompute on-time rate from judged contracts (cancelled ones don't count)
+    if let Err(e) = sqlx::query(
+        r#"UPDATE agents a
+           SET on_time_pct = s.pct, updated_at = now()
+           FROM (
+               SELECT agent_id,
+                      (100.0 * COUNT(*) FILTER (WHERE NOT is_late) / COUNT(*))::REAL AS pct
+               FROM contracts
+               WHERE is_late IS NOT NULL AND status <> 'cancelled'
+               GROUP BY agent_id
+           ) s
+           WHERE a.id = s.agent_id AND a.on_time_pct IS DISTINCT FROM s.pct"#,
+    )
+    .execute(db)
+    .await
+    {
+        tracing::warn!("Recomputing on-time rates failed: {e}");
+    }
+
+    if !overdue.is_empty() {
+        tracing::info!("Flagged {} overdue contract(s)", overdue.len());
+    }
+}
+
+/// Close battles whose `battle_closes_at` has passed (open → reviewing) and tell
+/// the client the entries are ready to judge.
+pub async fn close_battles(db: &PgPool) {
+    let closed = match close_due_battles(db).await {
+        Ok(closed) => closed,
+        Err(e) => {
+            tracing::warn!("Closing battles failed: {e}");
+            return;
+        }
+    };
+
+    for job in &closed {
+        notifications::notify(
+            db,
+            job.client_id,
+            "battle_closed",
+            &format!("Your battle \"{}\" has closed; the entries are revealed to you for judging", job.title),
+            serde_json::json!({"job_id": job.id}),
+        )
+        .await;
+    }
+
+    if !closed.is_empty() {
+        tracing::info!("Closed {} battle(s)", closed.len());
+    }
+}
+
+async fn close_due_battles(db: &PgPool) -> AppResult<Vec<Job>> {
+    let mut tx = db.begin().await?;
+    let due = sqlx::query_as::<_, Job>(
+        r#"SELECT * FROM jobs
+           WHERE battle_mode AND state = 'open' AND battle_closes_at <= now()
+           FOR UPDATE SKIP LOCKED"#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut closed = Vec::with_capacity(due.len());
+    for job in &due {
+        closed.push(states::set_job_state(&mut tx, job, JobState::Reviewing).await?);
+    }
+    tx.commit().await?;
+
+    Ok(closed)
+}
+
+/// Expire pending offers past their `expires_at` and tell the agent's owner.
+pub async fn expire_offers(db: &PgPool) {
+    let expired = match expire_due_offers(db).await {
+        Ok(expired) => expired,
+        Err(e) => {
+            tracing::warn!("Expiring offers failed: {e}");
+            return;
+        }
+    };
+
+    for offer in &expired {
+        let recipient: Option<(Uuid, String)> = match sqlx::query_as(
+            r#"SELECT a.owner_id, j.title FROM agents a, jobs j
+               WHERE a.id = $1 AND j.id = $2"#,
+        )
+        .bind(offer.agent_id)
+        .bind(offer.job_id)
+        .fetch_optional(db)
+        .await
+        {
+            Ok(recipient) => recipient,
+            Err(e) => {
+                tracing::warn!(offer_id = %offer.id, "Looking up expired offer's owner failed: {e}");
+                continue;
+            }
+        };
+
+        if let Some((owner_id, title)) = recipient {
+            notifications::notify(
+                db,
+                owner_id,
+                "offer_expired",
+                &format!("Your offer on \"{title}\" expired without being accepted"),
+                serde_json::json!({"offer_id": offer.id, "job_id": offer.job_id}),
+            )
+            .await;
+        }
+    }
+
+    if !expired.is_empty() {
+        tracing::info!("Expired {} offer(s)", expired.len());
+    }
+}
+
+async fn expire_due_offers(db: &PgPool) -> AppResult<Vec<Offer>> {
+    let mut tx = db.begin().await?;
+    let due = sqlx::query_as::<_, Offer>(
+        r#"SELECT * FROM offers
+           WHERE status = 'pending' AND expires_at <= now()
+           FOR UPDATE SKIP LOCKED"#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut expired = Vec::with_capacity(due.len());
+    for offer in &due {
+        expired.push(states::set_offer_status(&mut tx, offer, OfferStatus::Expired).await?);
+    }
+    tx.commit().await?;
+
+    Ok(expired)
+}
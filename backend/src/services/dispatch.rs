@@ -0,0 +1,261 @@
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::services::{notifications, outbound};
+use crate::states::ContractStatus;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Deliveries give up after this many failed attempts.
+const MAX_ATTEMPTS: i32 = 8;
+/// First retry delay; doubles on every failure.
+const BASE_BACKOFF_SECS: i64 = 30;
+
+/// Queue a signed dispatch of a freshly funded contract (or milestone) to the
+/// agent's `endpoint_url`. No-op for agents without an endpoint.
+pub async fn enqueue(conn: &mut PgConnection, contract_id: Uuid, milestone: Option<&Milestone>) -> AppResult<()> {
+    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(contract_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
+        .bind(contract.agent_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let Some(endpoint_url) = agent.endpoint_url.filter(|u| !u.trim().is_empty()) else {
+        return Ok(());
+    };
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(contract.job_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let requirements = sqlx::query_as::<_, JobRequirement>(
+        "SELECT * FROM job_requirements WHERE job_id = $1"
+    )
+    .bind(job.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let payload = serde_json::json!({
+        "event": "contract.funded",
+        "contract": contract,
+        "milestone": milestone,
+        "job": job,
+        "requirements": requirements,
+    });
+
+    sqlx::query(
+        r#"INSERT INTO job_dispatches (contract_id, milestone_id, agent_id, endpoint_url, payload)
+           VALUES ($1, $2, $3, $4, $5)"#,
+    )
+    .bind(contract.id)
+    .bind(milestone.map(|m| m.id))
+    .bind(agent.id)
+    .bind(endpoint_url)
+    .bind(payload)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Dispatch worker: POSTs due dispatches, retrying failures with exponential backoff.
+pub async fn run_dispatches(db: &PgPool, http: &reqwest::Client, public_api_url: &str, signing_key: &str) {
+    // Lease due rows so a slow delivery is not picked up again by the next tick
+    let due = sqlx::query_as::<_, JobDispatch>(
+        r#"UPDATE job_dispatches SET next_attempt_at = now() + interval '5 minutes'
+           WHERE id IN (
+               SELECT id FROM job_dispatches
+               WHERE status = 'pending' AND next_attempt_at <= now()
+               ORDER BY next_attempt_at
+               LIMIT 20
+               FOR UPDATE SKIP LOCKED
+           )
+           RETURNING *"#,
+    )
+    .fetch_all(db)
+    .await;
+    let due = match due {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!("Dispatch scan failed: {e}");
+            return;
+        }
+    };
+
+    for dispatch in due {
+        if let Err(e) = deliver(db, http, &dispatch, public_api_url, signing_key).await {
+            tracing::warn!(dispatch_id = %dispatch.id, "Dispatch bookkeeping failed: {e}");
+        }
+    }
+}
+
+async fn deliver(
+    db: &PgPool,
+    http: &reqwest::Client,
+    dispatch: &JobDispatch,
+    public_api_url: &str,
+    signing_key: &str,
+) -> AppResult<()> {
+    let status: (ContractStatus,) = sqlx::query_as("SELECT status FROM contracts WHERE id = $1")
+        .bind(dispatch.contract_id)
+        .fetch_one(db)
+        .await?;
+
+    if status.0 != ContractStatus::Active {
+        sqlx::query(
+            "UPDATE job_dispatches SET status = 'failed', last_error = 'Contract is no longer active' WHERE id = $1"
+        )
+        .bind(dispatch.id)
+        .execute(db)
+        .await?;
+        return Ok(());
+    }
+
+    let mut payload = dispatch.payload.clone();
+    payload["dispatch_id"] = serde_json::json!(dispatch.id);
+    payload["callback_url"] = serde_json::json!(format!("{public_api_url}/api/dispatches/{}/callback", dispatch.id));
+    payload["callback_token"] = serde_json::json!(callback_token(signing_key, dispatch.id));
+    let body = serde_json::to_vec(&payload).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let secret = agent_secret(db, dispatch.agent_id).await?;
+    let timestamp = Utc::now().timestamp();
+
+    let error = match outbound::check_url(&dispatch.endpoint_url).await {
+        Err(e) => Some(e),
+        Ok(()) => {
+            let result = http
+                .post(&dispatch.endpoint_url)
+                .header("Content-Type", "application/json")
+                .header("X-Lobster-Dispatch-Id", dispatch.id.to_string())
+                .header("X-Lobster-Timestamp", timestamp.to_string())
+                .header("X-Lobster-Signature", format!("sha256={}", sign(&secret, timestamp, &body)))
+                .body(body)
+                .send()
+                .await;
+
+            match result {
+                Ok(resp) if resp.status().is_success() => None,
+                Ok(resp) => Some(format!("Endpoint responded {}", resp.status())),
+                Err(e) => Some(e.to_string()),
+            }
+        }
+    };
+
+    let Some(error) = error else {
+        // The agent may already have called back; don't downgrade a completed dispatch
+        sqlx::query(
+            r#"UPDATE job_dispatches
+               SET status = 'delivered', attempts = attempts + 1, delivered_at = now(), last_error = NULL
+               WHERE id = $1 AND status = 'pending'"#,
+        )
+        .bind(dispatch.id)
+        .execute(db)
+        .await?;
+        return Ok(());
+    };
+
+    let attempts = dispatch.attempts + 1;
+    let gave_up = attempts >= MAX_ATTEMPTS;
+    let next_attempt_at = Utc::now() + Duration::seconds(BASE_BACKOFF_SECS << (attempts - 1).min(10));
+
+    sqlx::query(
+        r#"UPDATE job_dispatches
+           SET attempts = $1, last_error = $2, next_attempt_at = $3,
+               status = CASE WHEN $4 THEN 'failed' ELSE status END
+           WHERE id = $5 AND status = 'pending'"#,
+    )
+    .bind(attempts)
+    .bind(&error)
+    .bind(next_attempt_at)
+    .bind(gave_up)
+    .bind(dispatch.id)
+    .execute(db)
+    .await?;
+
+    if gave_up {
+        let owner: (Uuid,) = sqlx::query_as("SELECT owner_id FROM agents WHERE id = $1")
+            .bind(dispatch.agent_id)
+            .fetch_one(db)
+            .await?;
+
+        notifications::notify(
+            db,
+            owner.0,
+            "dispatch_failed",
+            &format!("Could not deliver a funded contract to your agent's endpoint after {attempts} attempts; submit the work manually"),
+            serde_json::json!({"contract_id": dispatch.contract_id, "dispatch_id": dispatch.id, "last_error": error}),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// The agent's HMAC key for dispatch signatures, generated on first use.
+pub async fn agent_secret(db: &PgPool, agent_id: Uuid) -> AppResult<String> {
+    let row: (String,) = sqlx::query_as(
+        "UPDATE agents SET dispatch_secret = COALESCE(dispatch_secret, $2) WHERE id = $1 RETURNING dispatch_secret"
+    )
+    .bind(agent_id)
+    .bind(new_secret())
+    .fetch_one(db)
+    .await?;
+
+    Ok(row.0)
+}
+
+/// Replace the agent's dispatch key; deliveries from now on are signed with the new one.
+pub async fn rotate_secret(db: &PgPool, agent_id: Uuid) -> AppResult<String> {
+    let row: (String,) = sqlx::query_as(
+        "UPDATE agents SET dispatch_secret = $2, updated_at = now() WHERE id = $1 RETURNING dispatch_secret"
+    )
+    .bind(agent_id)
+    .bind(new_secret())
+    .fetch_one(db)
+    .await?;
+
+    Ok(row.0)
+}
+
+/// Bearer token that authorizes the callback for one dispatch. Derived rather than
+/// stored, so the database never holds a usable credential.
+pub fn callback_token(signing_key: &str, dispatch_id: Uuid) -> String {
+    let mut mac = HmacSha256::new_from_slice(signing_key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("dispatch-callback:{dispatch_id}").as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Constant-time check of a callback token.
+pub fn verify_callback_token(signing_key: &str, dispatch_id: Uuid, token: &str) -> bool {
+    let Ok(token) = hex::decode(token) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(signing_key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("dispatch-callback:{dispatch_id}").as_bytes());
+    mac.verify_slice(&token).is_ok()
+}
+
+/// `hex(HMAC-SHA256(secret, "{timestamp}.{body}"))` — what agents recompute to
+/// authenticate a dispatch.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn new_secret() -> String {
+    hex::encode(rand::thread_rng().gen::<[u8; 32]>())
+}
@@ -1,172 +1,441 @@
-use sqlx::PgPool;
+//! Escrow moves. Every function runs on the caller's connection — normally an open
+//! transaction — and locks the rows it changes with `SELECT ... FOR UPDATE`, so a
+//! flow either commits as a whole or not at all. Lock order: contract → escrow → job.
+
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
 use crate::models::*;
+use crate::states::{self, ContractStatus, EscrowState, JobState, MilestoneState, StateMachine, SubmissionStatus};
 
 /// Fund an escrow account (none → funded)
-pub async fn fund_escrow(db: &PgPool, contract_id: Uuid, user_id: Uuid) -> AppResult<EscrowAccount> {
-    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
-        .bind(contract_id)
-        .fetch_optional(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+pub async fn fund_escrow(conn: &mut PgConnection, contract_id: Uuid, user_id: Uuid) -> AppResult<EscrowAccount> {
+    let contract = lock_contract(conn, contract_id).await?;
 
     if contract.client_id != user_id {
         return Err(AppError::Forbidden("Only the client can fund escrow".into()));
     }
 
-    let escrow = sqlx::query_as::<_, EscrowAccount>(
-        "SELECT * FROM escrow_accounts WHERE contract_id = $1"
-    )
-    .bind(contract_id)
-    .fetch_optional(db)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Escrow account not found".into()))?;
-
-    if escrow.state != "none" {
-        return Err(AppError::BadRequest(format!("Escrow is in state '{}', cannot fund", escrow.state)));
-    }
+    let escrow = contract_escrow(conn, contract_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow account not found; milestone contracts are funded per milestone".into()))?;
 
-    let updated = sqlx::query_as::<_, EscrowAccount>(
-        "UPDATE escrow_accounts SET state = 'funded', funded_at = now() WHERE id = $1 RETURNING *"
-    )
-    .bind(escrow.id)
-    .fetch_one(db)
-    .await?;
+    let updated = states::set_escrow_state(conn, &escrow, EscrowState::Funded).await?;
+    write_ledger(conn, escrow.id, "fund", escrow.amount_lamports).await?;
 
-    // Ledger entry
-    sqlx::query("INSERT INTO escrow_ledger_entries (escrow_id, entry_type, amount_lamports) VALUES ($1, 'fund', $2)")
-        .bind(escrow.id)
-        .bind(escrow.amount_lamports)
-        .execute(db)
-        .await?;
+    if contract.battle_rank.is_some() {
+        return pay_battle_prize(conn, &contract, &updated).await;
+    }
 
     // Move job to in_progress
-    sqlx::query("UPDATE jobs SET state = 'in_progress', updated_at = now() WHERE id = $1")
-        .bind(contract.job_id)
-        .execute(db)
-        .await?;
+    states::set_job_state_by_id(conn, contract.job_id, JobState::InProgress).await?;
 
     Ok(updated)
 }
 
 /// Lock escrow (funded → locked) — when work is submitted
-pub async fn lock_escrow(db: &PgPool, contract_id: Uuid) -> AppResult<EscrowAccount> {
-    let escrow = sqlx::query_as::<_, EscrowAccount>(
-        "SELECT * FROM escrow_accounts WHERE contract_id = $1"
-    )
-    .bind(contract_id)
-    .fetch_optional(db)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Escrow account not found".into()))?;
+pub async fn lock_escrow(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<EscrowAccount> {
+    let escrow = contract_escrow(conn, contract_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow account not found".into()))?;
 
-    if escrow.state != "funded" {
+    if escrow.state != EscrowState::Funded {
         return Err(AppError::BadRequest("Escrow must be funded to lock".into()));
     }
 
+    let updated = states::set_escrow_state(conn, &escrow, EscrowState::Locked).await?;
+    write_ledger(conn, escrow.id, "lock", escrow.funded_lamports()).await?;
+
+    Ok(updated)
+}
+
+/// Unlock escrow (locked → funded) — when the client rejects a submission
+pub async fn unlock_escrow(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<EscrowAccount> {
+    let escrow = contract_escrow(conn, contract_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow account not found".into()))?;
+
+    if escrow.state != EscrowState::Locked {
+        return Err(AppError::BadRequest("Escrow must be locked to unlock".into()));
+    }
+
+    let updated = states::set_escrow_state(conn, &escrow, EscrowState::Funded).await?;
+    write_ledger(conn, escrow.id, "unlock", escrow.funded_lamports()).await?;
+
+    Ok(updated)
+}
+
+/// Top up escrow by `extra` — when an amendment raises the agreed price.
+/// Unfunded escrow just grows its target amount; on funded/locked escrow the extra
+/// is owed by the client (`topup_due_lamports`) until `fund_top_up`, and blocks release
+/// (auto-release cancels it instead, see `cancel_top_up`).
+pub async fn top_up_escrow(conn: &mut PgConnection, contract_id: Uuid, extra: i64) -> AppResult<EscrowAccount> {
+    let escrow = contract_escrow(conn, contract_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow account not found".into()))?;
+
+    if !matches!(escrow.state, EscrowState::None | EscrowState::Funded | EscrowState::Locked) {
+        return Err(AppError::BadRequest(format!("Escrow is in state '{}', cannot top up", escrow.state)));
+    }
+    let due = if escrow.state == EscrowState::None { 0 } else { extra };
+
     let updated = sqlx::query_as::<_, EscrowAccount>(
-        "UPDATE escrow_accounts SET state = 'locked' WHERE id = $1 RETURNING *"
+        r#"UPDATE escrow_accounts SET amount_lamports = amount_lamports + $1, topup_due_lamports = topup_due_lamports + $2
+           WHERE id = $3 RETURNING *"#
     )
+    .bind(extra)
+    .bind(due)
     .bind(escrow.id)
-    .fetch_one(db)
+    .fetch_one(&mut *conn)
     .await?;
 
-    sqlx::query("INSERT INTO escrow_ledger_entries (escrow_id, entry_type, amount_lamports) VALUES ($1, 'lock', $2)")
-        .bind(escrow.id)
-        .bind(escrow.amount_lamports)
-        .execute(db)
-        .await?;
-
     Ok(updated)
 }
 
-/// Release escrow (locked → released) — on job approval
-pub async fn release_escrow(db: &PgPool, contract_id: Uuid, user_id: Uuid) -> AppResult<EscrowAccount> {
-    let contract = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
-        .bind(contract_id)
-        .fetch_optional(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Contract not found".into()))?;
+/// Fund an outstanding amendment top-up on funded/locked escrow (client only).
+pub async fn fund_top_up(conn: &mut PgConnection, contract_id: Uuid, user_id: Uuid) -> AppResult<EscrowAccount> {
+    let contract = lock_contract(conn, contract_id).await?;
 
     if contract.client_id != user_id {
-        return Err(AppError::Forbidden("Only the client can release escrow".into()));
+        return Err(AppError::Forbidden("Only the client can fund escrow".into()));
     }
 
-    let escrow = sqlx::query_as::<_, EscrowAccount>(
-        "SELECT * FROM escrow_accounts WHERE contract_id = $1"
-    )
-    .bind(contract_id)
-    .fetch_optional(db)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Escrow not found".into()))?;
+    let escrow = contract_escrow(conn, contract_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow account not found".into()))?;
 
-    if escrow.state != "locked" {
-        return Err(AppError::BadRequest("Escrow must be locked to release".into()));
+    if escrow.topup_due_lamports <= 0 {
+        return Err(AppError::BadRequest("No top-up is due on this escrow".into()));
+    }
+    if !matches!(escrow.state, EscrowState::Funded | EscrowState::Locked) {
+        return Err(AppError::BadRequest(format!("Escrow is in state '{}', cannot fund a top-up", escrow.state)));
     }
 
     let updated = sqlx::query_as::<_, EscrowAccount>(
-        "UPDATE escrow_accounts SET state = 'released', released_at = now() WHERE id = $1 RETURNING *"
+        "UPDATE escrow_accounts SET topup_due_lamports = 0 WHERE id = $1 RETURNING *"
     )
     .bind(escrow.id)
-    .fetch_one(db)
+    .fetch_one(&mut *conn)
     .await?;
+    write_ledger(conn, escrow.id, "fund", escrow.topup_due_lamports).await?;
 
-    sqlx::query("INSERT INTO escrow_ledger_entries (escrow_id, entry_type, amount_lamports) VALUES ($1, 'release', $2)")
-        .bind(escrow.id)
-        .bind(escrow.amount_lamports)
-        .execute(db)
-        .await?;
+    Ok(updated)
+}
 
-    // Complete contract + job
-    sqlx::query("UPDATE contracts SET status = 'completed', completed_at = now() WHERE id = $1")
-        .bind(contract_id)
-        .execute(db)
-        .await?;
+/// Cancel an unfunded amendment top-up (auto-release only): the escrow and the
+/// contract price fall back to what the client actually paid in.
+/// Returns the cancelled amount, 0 when nothing was due.
+pub async fn cancel_top_up(conn: &mut PgConnection, contract: &Contract) -> AppResult<i64> {
+    let escrow = contract_escrow(conn, contract.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow not found".into()))?;
 
-    sqlx::query("UPDATE jobs SET state = 'completed', updated_at = now() WHERE id = $1")
-        .bind(contract.job_id)
-        .execute(db)
+    let due = escrow.topup_due_lamports;
+    if due <= 0 {
+        return Ok(0);
+    }
+
+    sqlx::query(
+        "UPDATE escrow_accounts SET amount_lamports = amount_lamports - $1, topup_due_lamports = 0 WHERE id = $2"
+    )
+    .bind(due)
+    .bind(escrow.id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE contracts SET agreed_price_lamports = agreed_price_lamports - $1 WHERE id = $2")
+        .bind(due)
+        .bind(contract.id)
+        .execute(&mut *conn)
         .await?;
+    write_ledger(conn, escrow.id, "cancel_topup", due).await?;
+
+    Ok(due)
+}
+
+/// Release escrow (locked → released) — on job approval
+pub async fn release_escrow(conn: &mut PgConnection, contract_id: Uuid, user_id: Uuid) -> AppResult<EscrowAccount> {
+    let contract = lock_contract(conn, contract_id).await?;
+
+    if contract.client_id != user_id {
+        return Err(AppError::Forbidden("Only the client can release escrow".into()));
+    }
+
+    release_contract_escrow(conn, &contract).await
+}
+
+/// Release a contract's single escrow (locked → released) and complete the contract.
+/// Callers are responsible for authorization (client approval or the auto-release worker).
+pub async fn release_contract_escrow(conn: &mut PgConnection, contract: &Contract) -> AppResult<EscrowAccount> {
+    let escrow = contract_escrow(conn, contract.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Escrow not found".into()))?;
+
+    if escrow.state != EscrowState::Locked {
+        return Err(AppError::BadRequest("Escrow must be locked to release".into()));
+    }
+    if escrow.topup_due_lamports > 0 {
+        return Err(AppError::BadRequest(format!(
+            "The client must fund the {} lamport amendment top-up before release",
+            escrow.topup_due_lamports
+        )));
+    }
+
+    let updated = states::set_escrow_state(conn, &escrow, EscrowState::Released).await?;
+    write_ledger(conn, escrow.id, "release", escrow.amount_lamports).await?;
+
+    complete_contract(conn, contract).await?;
+
+    Ok(updated)
+}
+
+/// Battle prizes pay out as soon as they are funded (funded → locked → released):
+/// the work was delivered and accepted when the client picked the winner.
+async fn pay_battle_prize(conn: &mut PgConnection, contract: &Contract, escrow: &EscrowAccount) -> AppResult<EscrowAccount> {
+    let locked = states::set_escrow_state(conn, escrow, EscrowState::Locked).await?;
+    write_ledger(conn, escrow.id, "lock", escrow.amount_lamports).await?;
+    let released = states::set_escrow_state(conn, &locked, EscrowState::Released).await?;
+    write_ledger(conn, escrow.id, "release", escrow.amount_lamports).await?;
+
+    states::set_contract_status(conn, contract, ContractStatus::Completed).await?;
+
+    // Only the winner completed the job
+    if contract.battle_rank == Some(1) {
+        sqlx::query("UPDATE agents SET total_jobs_completed = total_jobs_completed + 1, updated_at = now() WHERE id = $1")
+            .bind(contract.agent_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(released)
+}
+
+/// Mark contract + job completed and credit the agent.
+pub async fn complete_contract(conn: &mut PgConnection, contract: &Contract) -> AppResult<()> {
+    states::set_contract_status(conn, contract, ContractStatus::Completed).await?;
+    states::set_job_state_by_id(conn, contract.job_id, JobState::Completed).await?;
 
     // Increment agent completed jobs
     sqlx::query("UPDATE agents SET total_jobs_completed = total_jobs_completed + 1, updated_at = now() WHERE id = $1")
         .bind(contract.agent_id)
-        .execute(db)
+        .execute(&mut *conn)
         .await?;
 
-    Ok(updated)
+    Ok(())
+}
+
+/// Freeze escrow (funded/locked → frozen) — while a dispute is open.
+/// Covers every unreleased tranche of the contract; unfunded milestones are left alone.
+pub async fn freeze_escrow(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<Vec<EscrowAccount>> {
+    let frozen = move_all(conn, contract_id, EscrowState::Frozen, "freeze").await?;
+
+    if frozen.is_empty() {
+        return Err(AppError::BadRequest("Escrow must be funded or locked to freeze".into()));
+    }
+
+    Ok(frozen)
+}
+
+/// Refund escrow (funded/locked/frozen → refunded) — on dispute resolution or cancellation
+pub async fn refund_escrow(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<Vec<EscrowAccount>> {
+    let refunded = move_all(conn, contract_id, EscrowState::Refunded, "refund").await?;
+
+    if refunded.is_empty() {
+        return Err(AppError::BadRequest("Escrow must be funded, locked or frozen to refund".into()));
+    }
+
+    Ok(refunded)
 }
 
-/// Refund escrow (funded/locked → refunded) — on dispute resolution or cancellation
-pub async fn refund_escrow(db: &PgPool, contract_id: Uuid) -> AppResult<EscrowAccount> {
-    let escrow = sqlx::query_as::<_, EscrowAccount>(
-        "SELECT * FROM escrow_accounts WHERE contract_id = $1"
+/// Settle frozen escrow after a dispute (frozen → released | split).
+/// `agent_pct` of each frozen tranche goes to the agent, the remainder is refunded to the client.
+pub async fn settle_escrow(conn: &mut PgConnection, contract_id: Uuid, agent_pct: i32) -> AppResult<Vec<EscrowAccount>> {
+    if !(1..=100).contains(&agent_pct) {
+        return Err(AppError::BadRequest("Agent share must be between 1 and 100 percent".into()));
+    }
+
+    let frozen = sqlx::query_as::<_, EscrowAccount>(
+        "SELECT * FROM escrow_accounts WHERE contract_id = $1 AND state = 'frozen' ORDER BY created_at ASC FOR UPDATE"
     )
     .bind(contract_id)
-    .fetch_optional(db)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Escrow not found".into()))?;
+    .fetch_all(&mut *conn)
+    .await?;
 
-    if !["funded", "locked"].contains(&escrow.state.as_str()) {
-        return Err(AppError::BadRequest("Escrow must be funded or locked to refund".into()));
+    if frozen.is_empty() {
+        return Err(AppError::BadRequest("Escrow must be frozen to settle".into()));
     }
 
-    let updated = sqlx::query_as::<_, EscrowAccount>(
-        "UPDATE escrow_accounts SET state = 'refunded' WHERE id = $1 RETURNING *"
+    let new_state = if agent_pct == 100 { EscrowState::Released } else { EscrowState::Split };
+    let mut settled = Vec::with_capacity(frozen.len());
+    for escrow in frozen {
+        // An unfunded top-up was never paid in, so it isn't paid out either
+        let agent_amount = escrow.funded_lamports() * agent_pct as i64 / 100;
+        let client_amount = escrow.funded_lamports() - agent_amount;
+
+        let updated = states::set_escrow_state(conn, &escrow, new_state).await?;
+
+        if agent_amount > 0 {
+            write_ledger(conn, escrow.id, "release", agent_amount).await?;
+        }
+        if client_amount > 0 {
+            write_ledger(conn, escrow.id, "refund", client_amount).await?;
+        }
+        settled.push(updated);
+    }
+
+    Ok(settled)
+}
+
+// ─── Milestone tranches ─────────────────────────────────────
+
+/// Fund one milestone's tranche (none → funded); the job starts on the first funded tranche.
+pub async fn fund_milestone_escrow(conn: &mut PgConnection, milestone: &Milestone, contract: &Contract) -> AppResult<EscrowAccount> {
+    let escrow = milestone_escrow(conn, milestone.id).await?;
+
+    let updated = states::set_escrow_state(conn, &escrow, EscrowState::Funded).await?;
+    write_ledger(conn, escrow.id, "fund", escrow.amount_lamports).await?;
+
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1 FOR UPDATE")
+        .bind(contract.job_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    if job.state == JobState::Matched {
+        states::set_job_state(conn, &job, JobState::InProgress).await?;
+    }
+
+    Ok(updated)
+}
+
+/// Lock one milestone's tranche (funded → locked) — when its work is submitted
+pub async fn lock_milestone_escrow(conn: &mut PgConnection, milestone_id: Uuid) -> AppResult<EscrowAccount> {
+    let escrow = milestone_escrow(conn, milestone_id).await?;
+
+    if escrow.state != EscrowState::Funded {
+        return Err(AppError::BadRequest("Milestone must be funded before submitting work".into()));
+    }
+
+    let updated = states::set_escrow_state(conn, &escrow, EscrowState::Locked).await?;
+    write_ledger(conn, escrow.id, "lock", escrow.amount_lamports).await?;
+
+    Ok(updated)
+}
+
+/// Release one milestone's tranche (locked → released), accept its submission,
+/// and complete the contract once every milestone is released.
+pub async fn release_milestone(conn: &mut PgConnection, contract: &Contract, milestone_id: Uuid) -> AppResult<Milestone> {
+    let escrow = milestone_escrow(conn, milestone_id).await?;
+
+    if escrow.state != EscrowState::Locked {
+        return Err(AppError::BadRequest("Milestone escrow must be locked to release".into()));
+    }
+
+    states::set_escrow_state(conn, &escrow, EscrowState::Released).await?;
+    write_ledger(conn, escrow.id, "release", escrow.amount_lamports).await?;
+
+    let pending = sqlx::query_as::<_, Submission>(
+        "SELECT * FROM submissions WHERE milestone_id = $1 AND status = 'pending' FOR UPDATE"
     )
-    .bind(escrow.id)
-    .fetch_one(db)
+    .bind(milestone_id)
+    .fetch_all(&mut *conn)
     .await?;
+    for submission in &pending {
+        states::set_submission_status(conn, submission, SubmissionStatus::Accepted, None).await?;
+    }
 
-    sqlx::query("INSERT INTO escrow_ledger_entries (escrow_id, entry_type, amount_lamports) VALUES ($1, 'refund', $2)")
-        .bind(escrow.id)
-        .bind(escrow.amount_lamports)
-        .execute(db)
+    let milestone = sqlx::query_as::<_, Milestone>("SELECT * FROM milestones WHERE id = $1 FOR UPDATE")
+        .bind(milestone_id)
+        .fetch_one(&mut *conn)
         .await?;
+    let milestone = states::set_milestone_state(conn, &milestone, MilestoneState::Released).await?;
 
-    Ok(updated)
+    let remaining: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM milestones WHERE contract_id = $1 AND state != 'released'"
+    )
+    .bind(contract.id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if remaining.0 == 0 {
+        complete_contract(conn, contract).await?;
+    }
+
+    Ok(milestone)
+}
+
+/// Load and lock a contract row; the first lock every escrow flow takes.
+pub async fn lock_contract(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<Contract> {
+    sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1 FOR UPDATE")
+        .bind(contract_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contract not found".into()))
 }
 
+/// Create the escrow account(s) for a new contract: one per milestone tranche, or a single account.
+pub async fn open_escrow(conn: &mut PgConnection, contract: &Contract, milestones: &[Milestone]) -> AppResult<()> {
+    if milestones.is_empty() {
+        sqlx::query("INSERT INTO escrow_accounts (contract_id, amount_lamports) VALUES ($1, $2)")
+            .bind(contract.id)
+            .bind(contract.agreed_price_lamports)
+            .execute(&mut *conn)
+            .await?;
+    } else {
+        for m in milestones {
+            sqlx::query(
+                "INSERT INTO escrow_accounts (contract_id, milestone_id, amount_lamports) VALUES ($1, $2, $3)"
+            )
+            .bind(contract.id)
+            .bind(m.id)
+            .bind(m.amount_lamports)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn contract_escrow(conn: &mut PgConnection, contract_id: Uuid) -> AppResult<Option<EscrowAccount>> {
+    Ok(sqlx::query_as::<_, EscrowAccount>(
+        "SELECT * FROM escrow_accounts WHERE contract_id = $1 AND milestone_id IS NULL FOR UPDATE"
+    )
+    .bind(contract_id)
+    .fetch_optional(&mut *conn)
+    .await?)
+}
 
+async fn milestone_escrow(conn: &mut PgConnection, milestone_id: Uuid) -> AppResult<EscrowAccount> {
+    sqlx::query_as::<_, EscrowAccount>("SELECT * FROM escrow_accounts WHERE milestone_id = $1 FOR UPDATE")
+        .bind(milestone_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Milestone escrow not found".into()))
+}
 
+/// Move every tranche of a contract that may legally reach `next`, writing a ledger entry for each.
+async fn move_all(conn: &mut PgConnection, contract_id: Uuid, next: EscrowState, entry_type: &str) -> AppResult<Vec<EscrowAccount>> {
+    let accounts = sqlx::query_as::<_, EscrowAccount>(
+        "SELECT * FROM escrow_accounts WHERE contract_id = $1 ORDER BY created_at ASC FOR UPDATE"
+    )
+    .bind(contract_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut moved = Vec::new();
+    for escrow in accounts.iter().filter(|e| e.state.can_transition_to(next)) {
+        let updated = states::set_escrow_state(conn, escrow, next).await?;
+        write_ledger(conn, escrow.id, entry_type, escrow.funded_lamports()).await?;
+        moved.push(updated);
+    }
+
+    Ok(moved)
+}
+
+async fn write_ledger(conn: &mut PgConnection, escrow_id: Uuid, entry_type: &str, amount: i64) -> AppResult<()> {
+    sqlx::query("INSERT INTO escrow_ledger_entries (escrow_id, entry_type, amount_lamports) VALUES ($1, $2, $3)")
+        .bind(escrow_id)
+        .bind(entry_type)
+        .bind(amount)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
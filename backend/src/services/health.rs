@@ -0,0 +1,127 @@
+use std::time::Instant;
+
+use sqlx::PgPool;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::UptimeStats;
+use crate::services::outbound;
+
+/// Probes in flight at once.
+const CONCURRENCY: usize = 16;
+
+/// Health prober: GETs `endpoint_url + path` on every active agent with an endpoint
+/// and records an up/down sample with latency. Up means a 2xx within the client timeout.
+pub async fn probe_all(db: &PgPool, http: &reqwest::Client, path: &str) {
+    let targets: Vec<(Uuid, String)> = match sqlx::query_as(
+        r#"SELECT id, endpoint_url FROM agents
+           WHERE status = 'active' AND endpoint_url IS NOT NULL AND endpoint_url <> ''"#,
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(targets) => targets,
+        Err(e) => {
+            tracing::warn!("Health probe scan failed: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut down = 0;
+    for chunk in targets.chunks(CONCURRENCY) {
+        let mut probes = JoinSet::new();
+        for (agent_id, endpoint_url) in chunk {
+            let url = format!("{}/{}", endpoint_url.trim_end_matches('/'), path.trim_start_matches('/'));
+            let (http, agent_id) = (http.clone(), *agent_id);
+            probes.spawn(async move { (agent_id, probe(&http, &url).await) });
+        }
+
+        while let Some(res) = probes.join_next().await {
+            let (agent_id, sample) = match res {
+                Ok(probed) => probed,
+                Err(e) => {
+                    tracing::warn!("Health probe task failed: {e}");
+                    continue;
+                }
+            };
+            if !sample.is_up {
+                down += 1;
+            }
+            if let Err(e) = sqlx::query(
+                r#"INSERT INTO agent_health_checks (agent_id, is_up, latency_ms, status_code, error)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+            )
+            .bind(agent_id)
+            .bind(sample.is_up)
+            .bind(sample.latency_ms)
+            .bind(sample.status_code)
+            .bind(sample.error)
+            .execute(db)
+            .await
+            {
+                tracing::warn!(agent_id = %agent_id, "Recording health sample failed: {e}");
+            }
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM agent_health_checks WHERE checked_at < now() - interval '30 days'")
+        .execute(db)
+        .await
+    {
+        tracing::warn!("Pruning old health samples failed: {e}");
+    }
+
+    if !targets.is_empty() {
+        tracing::info!("Probed {} agent endpoint(s), {down} down", targets.len());
+    }
+}
+
+struct Sample {
+    is_up: bool,
+    latency_ms: Option<i32>,
+    status_code: Option<i32>,
+    error: Option<String>,
+}
+
+async fn probe(http: &reqwest::Client, url: &str) -> Sample {
+    if let Err(e) = outbound::check_url(url).await {
+        return Sample { is_up: false, latency_ms: None, status_code: None, error: Some(e) };
+    }
+
+    let started = Instant::now();
+    match http.get(url).send().await {
+        Ok(resp) => Sample {
+            is_up: resp.status().is_success(),
+            latency_ms: Some(started.elapsed().as_millis() as i32),
+            status_code: Some(resp.status().as_u16() as i32),
+            error: None,
+        },
+        Err(e) => Sample {
+            is_up: false,
+            latency_ms: None,
+            status_code: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Uptime over 24h / 7d / 30d plus the latest sample.
+pub async fn uptime(db: &PgPool, agent_id: Uuid) -> AppResult<UptimeStats> {
+    let stats = sqlx::query_as::<_, UptimeStats>(
+        r#"SELECT
+             (100.0 * AVG(is_up::int) FILTER (WHERE checked_at > now() - interval '24 hours'))::float8 AS uptime_24h_pct,
+             (100.0 * AVG(is_up::int) FILTER (WHERE checked_at > now() - interval '7 days'))::float8 AS uptime_7d_pct,
+             (100.0 * AVG(is_up::int))::float8 AS uptime_30d_pct,
+             AVG(latency_ms) FILTER (WHERE is_up AND checked_at > now() - interval '24 hours')::float8 AS avg_latency_ms_24h,
+             MAX(checked_at) AS last_checked_at,
+             (ARRAY_AGG(is_up ORDER BY checked_at DESC))[1] AS is_up
+           FROM agent_health_checks
+           WHERE agent_id = $1 AND checked_at > now() - interval '30 days'"#,
+    )
+    .bind(agent_id)
+    .fetch_one(db)
+    .await?;
+
+    Ok(stats)
+}
@@ -0,0 +1,221 @@
+//! Job creation shared by `POST /api/jobs`, cloning, templates and recurring
+//! schedules, plus the worker that runs due schedules.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+use crate::services::cron::CronSchedule;
+use crate::services::{notifications, requirement_checks};
+use crate::states::{self, JobState};
+
+pub const VISIBILITIES: [&str; 3] = ["public", "invite_only", "private"];
+
+/// Recurring jobs may fire at most once an hour.
+const MIN_SCHEDULE_INTERVAL_MINUTES: i64 = 60;
+
+pub fn validate_settings(visibility: &str, reward_pct: Option<i32>, closes_at: Option<DateTime<Utc>>) -> AppResult<()> {
+    if !VISIBILITIES.contains(&visibility) {
+        return Err(AppError::BadRequest(format!(
+            "visibility must be one of: {}",
+            VISIBILITIES.join(", ")
+        )));
+    }
+    // The winner always keeps part of the prize
+    if reward_pct.is_some_and(|pct| !(0..100).contains(&pct)) {
+        return Err(AppError::BadRequest("battle_partial_reward_pct must be between 0 and 99".into()));
+    }
+    if closes_at.is_some_and(|t| t <= Utc::now()) {
+        return Err(AppError::BadRequest("battle_closes_at must be in the future".into()));
+    }
+    Ok(())
+}
+
+pub fn validate_job_req(body: &CreateJobReq) -> AppResult<()> {
+    validate_settings(
+        body.visibility.as_deref().unwrap_or("public"),
+        body.battle_partial_reward_pct,
+        body.battle_closes_at,
+    )?;
+    for req in body.requirements.iter().flatten() {
+        if let Some(check_type) = &req.check_type {
+            requirement_checks::validate_config(check_type, req.check_config.as_ref())
+                .map_err(|e| AppError::BadRequest(format!("Requirement \"{}\": {e}", req.requirement)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate and insert a draft job with its requirements.
+pub async fn insert_job(conn: &mut PgConnection, client_id: Uuid, body: &CreateJobReq) -> AppResult<Job> {
+    validate_job_req(body)?;
+
+    let job = sqlx::query_as::<_, Job>(
+        r#"INSERT INTO jobs (client_id, title, description, budget_lamports, currency, currency_chain,
+           battle_mode, battle_max_submissions, battle_partial_reward_pct, battle_closes_at, deadline, tags, visibility)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+           RETURNING *"#,
+    )
+    .bind(client_id)
+    .bind(&body.title)
+    .bind(&body.description)
+    .bind(body.budget_lamports)
+    .bind(body.currency.as_deref().unwrap_or("USDC"))
+    .bind(body.currency_chain.as_deref().unwrap_or("solana"))
+    .bind(body.battle_mode.unwrap_or(false))
+    .bind(body.battle_max_submissions)
+    .bind(body.battle_partial_reward_pct)
+    .bind(body.battle_closes_at)
+    .bind(body.deadline)
+    .bind(body.tags.clone().unwrap_or_default())
+    .bind(body.visibility.as_deref().unwrap_or("public"))
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for req in body.requirements.iter().flatten() {
+        sqlx::query(
+            r#"INSERT INTO job_requirements (job_id, requirement, is_mandatory, check_type, check_config)
+               VALUES ($1, $2, $3, $4, $5)"#,
+        )
+        .bind(job.id)
+        .bind(&req.requirement)
+        .bind(req.is_mandatory.unwrap_or(true))
+        .bind(&req.check_type)
+        .bind(req.check_type.as_ref().and(req.check_config.as_ref()))
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(job)
+}
+
+/// The job a template produces when instantiated at `now`.
+pub fn job_req(template: &JobTemplate, now: DateTime<Utc>) -> AppResult<CreateJobReq> {
+    let requirements = serde_json::from_value(template.requirements.clone())
+        .map_err(|e| AppError::Internal(format!("Template requirements could not be read: {e}")))?;
+
+    Ok(CreateJobReq {
+        title: template.title.clone(),
+        description: template.description.clone(),
+        budget_lamports: template.budget_lamports,
+        currency: Some(template.currency.clone()),
+        currency_chain: Some(template.currency_chain.clone()),
+        battle_mode: Some(template.battle_mode),
+        battle_max_submissions: template.battle_max_submissions,
+        battle_partial_reward_pct: template.battle_partial_reward_pct,
+        battle_closes_at: template.battle_duration_hours.map(|h| now + Duration::hours(h.into())),
+        deadline: template.deadline_hours.map(|h| now + Duration::hours(h.into())),
+        tags: template.tags.clone(),
+        visibility: Some(template.visibility.clone()),
+        requirements,
+    })
+}
+
+/// Parse a schedule's cron expression, rejecting ones that never fire or fire
+/// more often than hourly. Returns the first run after `now`.
+pub fn first_run(cron: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let schedule = CronSchedule::parse(cron)?;
+    let first = schedule.next_after(now).ok_or("Schedule never fires")?;
+
+    let mut previous = first;
+    for _ in 0..50 {
+        let Some(next) = schedule.next_after(previous) else { break };
+        if next - previous < Duration::minutes(MIN_SCHEDULE_INTERVAL_MINUTES) {
+            return Err("Schedules can fire at most once an hour".into());
+        }
+        previous = next;
+    }
+    Ok(first)
+}
+
+/// Create (and optionally publish) a job for every schedule that is due, then
+/// advance it to its next tick. Ticks missed while the server was down run once.
+pub async fn run_schedules(db: &PgPool) {
+    let due: Vec<(Uuid,)> = match sqlx::query_as(
+        "SELECT id FROM job_schedules WHERE is_active AND next_run_at <= now()"
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!("Job schedule scan failed: {e}");
+            return;
+        }
+    };
+
+    for (schedule_id,) in due {
+        if let Err(e) = run_schedule(db, schedule_id).await {
+            tracing::warn!("Job schedule {schedule_id} failed: {e}");
+            if let Err(e) = sqlx::query(
+                r#"UPDATE job_schedules SET last_error = $1, last_run_at = now(),
+                   next_run_at = now() + interval '1 hour'
+                   WHERE id = $2"#,
+            )
+            .bind(e.to_string())
+            .bind(schedule_id)
+            .execute(db)
+            .await
+            {
+                tracing::warn!("Recording job schedule {schedule_id} failure failed: {e}");
+            }
+        }
+    }
+}
+
+async fn run_schedule(db: &PgPool, schedule_id: Uuid) -> AppResult<()> {
+    let mut tx = db.begin().await?;
+    // Skip schedules another instance is already running
+    let Some(schedule) = sqlx::query_as::<_, JobSchedule>(
+        r#"SELECT * FROM job_schedules
+           WHERE id = $1 AND is_active AND next_run_at <= now()
+           FOR UPDATE SKIP LOCKED"#,
+    )
+    .bind(schedule_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let template = sqlx::query_as::<_, JobTemplate>("SELECT * FROM job_templates WHERE id = $1")
+        .bind(schedule.template_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let now = Utc::now();
+    let mut job = insert_job(&mut tx, schedule.owner_id, &job_req(&template, now)?).await?;
+    if schedule.auto_publish {
+        job = states::set_job_state(&mut tx, &job, JobState::Open).await?;
+    }
+
+    // A schedule whose expression no longer parses stops rather than looping
+    let next_run = CronSchedule::parse(&schedule.cron).ok().and_then(|c| c.next_after(now));
+    sqlx::query(
+        r#"UPDATE job_schedules SET last_run_at = $1, last_job_id = $2, last_error = NULL,
+           next_run_at = $3, is_active = $4
+           WHERE id = $5"#,
+    )
+    .bind(now)
+    .bind(job.id)
+    .bind(next_run)
+    .bind(next_run.is_some())
+    .bind(schedule.id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let action = if schedule.auto_publish { "posted" } else { "drafted" };
+    notifications::notify(
+        db,
+        schedule.owner_id,
+        "scheduled_job_created",
+        &format!("Your recurring job \"{}\" was {action}", job.title),
+        serde_json::json!({"job_id": job.id, "schedule_id": schedule.id, "template_id": template.id}),
+    )
+    .await;
+
+    Ok(())
+}
@@ -1,6 +1,18 @@
 pub mod antifraud;
+pub mod auto_release;
+pub mod battle_rating;
+pub mod benchmarks;
+pub mod cron;
+pub mod deadlines;
+pub mod dispatch;
 pub mod escrow;
+pub mod health;
+pub mod job_templates;
+pub mod notifications;
+pub mod outbound;
 pub mod ranking;
+pub mod requirement_checks;
+pub mod verification;
 
 
 
@@ -0,0 +1,20 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Queue an in-app notification. Failures are logged, never propagated —
+/// a missed notification must not roll back the action that triggered it.
+pub async fn notify(db: &PgPool, user_id: Uuid, kind: &str, message: &str, metadata: serde_json::Value) {
+    let res = sqlx::query(
+        "INSERT INTO notifications (user_id, kind, message, metadata) VALUES ($1, $2, $3, $4)"
+    )
+    .bind(user_id)
+    .bind(kind)
+    .bind(message)
+    .bind(metadata)
+    .execute(db)
+    .await;
+
+    if let Err(e) = res {
+        tracing::warn!(%user_id, kind, "Failed to store notification: {e}");
+    }
+}
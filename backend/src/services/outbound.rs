@@ -0,0 +1,143 @@
+//! Outbound requests to agent-supplied URLs (health probes, verification,
+//! benchmarks, dispatches). Only http(s) to public addresses: clients built here
+//! refuse to connect to loopback, private, link-local (cloud metadata) and other
+//! non-public ranges, and never follow redirects.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+
+/// HTTP client for agent URLs with the given request timeout.
+pub fn client(timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        // A proxy would resolve the host itself, past the resolver below
+        .no_proxy()
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+}
+
+/// Reject URLs that aren't http(s) or whose host resolves to a non-public address.
+pub async fn check_url(url: &str) -> Result<(), String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("URL must use http or https".into());
+    }
+    let host = url.host_str().ok_or("URL has no host")?;
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let addrs = resolve(host.trim_start_matches('[').trim_end_matches(']'), port).await?;
+    if addrs.is_empty() {
+        return Err(format!("{host} did not resolve"));
+    }
+    Ok(())
+}
+
+/// Resolve `host`, failing if any address is not public.
+async fn resolve(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Could not resolve {host}: {e}"))?
+        .collect();
+
+    if let Some(addr) = addrs.iter().find(|a| !is_public(a.ip())) {
+        return Err(format!("{host} resolves to a non-public address ({})", addr.ip()));
+    }
+    Ok(addrs)
+}
+
+/// Resolver that fails rather than hand a non-public address to the connector,
+/// so a host can't pass `check_url` and then rebind to an internal address.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = resolve(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // shared address space (carrier-grade NAT)
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // benchmarking
+        || (a == 198 && (b == 18 || b == 19))
+        // reserved
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local
+        || (first & 0xfe00) == 0xfc00
+        // link-local
+        || (first & 0xffc0) == 0xfe80
+        // documentation
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8)
+        // NAT64 and IPv4-compatible forms can reach IPv4 ranges
+        || ip.segments()[..6] == [0x64, 0xff9b, 0, 0, 0, 0]
+        || ip.segments()[..6] == [0; 6])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_public_addresses_are_rejected() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1",
+            "0.0.0.0", "::1", "::", "fe80::1", "fd00::1", "::ffff:127.0.0.1", "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn check_url_rejects_bad_schemes_and_internal_hosts() {
+        for url in [
+            "file:///etc/passwd",
+            "ftp://example.com/",
+            "http://127.0.0.1:8080/health",
+            "http://[::1]/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://localhost/",
+        ] {
+            assert!(check_url(url).await.is_err(), "{url}");
+        }
+        assert!(check_url("https://93.184.215.14/").await.is_ok());
+    }
+}
@@ -1,6 +1,8 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::services::health;
+
 /// Lobster Score v1 (0–100) with Bayesian smoothing.
 ///
 /// Weights:
@@ -9,8 +11,9 @@ use uuid::Uuid;
 ///   15% on-time delivery rate
 ///   10% dispute/refund inverse score
 ///   10% consistency (rolling window)
-///   10% trust confidence (verification tier + account age)
-pub async fn compute_lobster_score(db: &PgPool, agent_id: Uuid) -> f32 {
+///   10% trust confidence (verification tier + account age, plus 7-day
+///       endpoint uptime when `use_uptime` is set and the agent has samples)
+pub async fn compute_lobster_score(db: &PgPool, agent_id: Uuid, use_uptime: bool) -> f32 {
     let agent = sqlx::query_as::<_, super::super::models::Agent>(
         "SELECT * FROM agents WHERE id = $1"
     )
@@ -128,7 +131,15 @@ pub async fn compute_lobster_score(db: &PgPool, agent_id: Uuid) -> f32 {
     };
     let age_days = (chrono::Utc::now() - agent.created_at).num_days() as f32;
     let age_score = (age_days / 90.0).min(1.0) * 100.0; // max at 90 days
-    let trust = (tier_score + age_score) / 2.0;
+    let uptime = if use_uptime {
+        health::uptime(db, agent_id).await.ok().and_then(|u| u.uptime_7d_pct)
+    } else {
+        None
+    };
+    let trust = match uptime {
+        Some(up) => (tier_score + age_score + up as f32) / 3.0,
+        None => (tier_score + age_score) / 2.0,
+    };
 
     // Weighted sum
     let score = completion * 0.35
@@ -142,7 +153,7 @@ pub async fn compute_lobster_score(db: &PgPool, agent_id: Uuid) -> f32 {
 }
 
 /// Recompute and persist scores for all active agents.
-pub async fn refresh_all_scores(db: &PgPool) {
+pub async fn refresh_all_scores(db: &PgPool, use_uptime: bool) {
     let agents: Vec<(uuid::Uuid,)> = sqlx::query_as(
         "SELECT id FROM agents WHERE status = 'active'"
     )
@@ -151,7 +162,7 @@ pub async fn refresh_all_scores(db: &PgPool) {
     .unwrap_or_default();
 
     for (agent_id,) in agents {
-        let score = compute_lobster_score(db, agent_id).await;
+        let score = compute_lobster_score(db, agent_id, use_uptime).await;
         let _ = sqlx::query("UPDATE agents SET lobster_score = $1, updated_at = now() WHERE id = $2")
             .bind(score)
             .bind(agent_id)
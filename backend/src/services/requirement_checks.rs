@@ -0,0 +1,231 @@
+//! Machine-checkable job requirements. A requirement may carry a `check_type` and
+//! `check_config`; every submission against the job is run through those checks
+//! before it is stored, and a failed mandatory check blocks it.
+
+use serde_json::Value;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::*;
+
+pub const CHECK_TYPES: [&str; 4] = ["json_schema", "regex", "max_length", "file_types"];
+
+/// Upper bound on compiled regex size, so a pattern can't exhaust memory.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Validate a requirement's check when the job is created.
+pub fn validate_config(check_type: &str, config: Option<&Value>) -> Result<(), String> {
+    let config = config.ok_or_else(|| format!("check_config is required for a {check_type} check"))?;
+    match check_type {
+        "json_schema" => {
+            let schema = config.get("schema").ok_or("json_schema check needs {schema}")?;
+            jsonschema::validator_for(schema).map_err(|e| format!("Invalid JSON Schema: {e}"))?;
+        }
+        "regex" => {
+            let pattern = config.get("pattern").and_then(Value::as_str).ok_or("regex check needs {pattern}")?;
+            compile(pattern)?;
+        }
+        "max_length" => {
+            config
+                .get("max_chars")
+                .and_then(Value::as_u64)
+                .filter(|n| *n > 0)
+                .ok_or("max_length check needs a positive {max_chars}")?;
+        }
+        "file_types" => {
+            let extensions = extensions(config);
+            if extensions.is_empty() {
+                return Err("file_types check needs {extensions: [\"pdf\", ...]}".into());
+            }
+        }
+        _ => return Err(format!("check_type must be one of: {}", CHECK_TYPES.join(", "))),
+    }
+    Ok(())
+}
+
+/// Run the job's machine checks against a candidate submission.
+pub async fn evaluate(
+    conn: &mut PgConnection,
+    job_id: Uuid,
+    content: &str,
+    artifacts_url: Option<&str>,
+) -> AppResult<Vec<RequirementCheckResult>> {
+    let requirements = sqlx::query_as::<_, JobRequirement>(
+        "SELECT * FROM job_requirements WHERE job_id = $1 AND check_type IS NOT NULL"
+    )
+    .bind(job_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(requirements
+        .into_iter()
+        .map(|r| {
+            let check_type = r.check_type.unwrap_or_default();
+            let outcome = run_check(&check_type, r.check_config.as_ref().unwrap_or(&Value::Null), content, artifacts_url);
+            RequirementCheckResult {
+                requirement_id: r.id,
+                requirement: r.requirement,
+                is_mandatory: r.is_mandatory,
+                check_type,
+                passed: outcome.is_ok(),
+                detail: outcome.err(),
+            }
+        })
+        .collect())
+}
+
+/// Evaluate the checks and refuse the submission if a mandatory one fails.
+pub async fn check_submission(
+    conn: &mut PgConnection,
+    job_id: Uuid,
+    content: &str,
+    artifacts_url: Option<&str>,
+) -> AppResult<Vec<RequirementCheckResult>> {
+    let results = evaluate(conn, job_id, content, artifacts_url).await?;
+
+    let failed: Vec<String> = results
+        .iter()
+        .filter(|r| r.is_mandatory && !r.passed)
+        .map(|r| format!("{} ({})", r.requirement, r.detail.as_deref().unwrap_or("failed")))
+        .collect();
+
+    if !failed.is_empty() {
+        return Err(AppError::BadRequest(format!("Mandatory requirement checks failed: {}", failed.join("; "))));
+    }
+
+    Ok(results)
+}
+
+/// Store the outcomes for a created submission.
+pub async fn record(conn: &mut PgConnection, submission_id: Uuid, results: &[RequirementCheckResult]) -> AppResult<()> {
+    for result in results {
+        sqlx::query(
+            "INSERT INTO submission_checks (submission_id, requirement_id, passed, detail) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(submission_id)
+        .bind(result.requirement_id)
+        .bind(result.passed)
+        .bind(&result.detail)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+fn run_check(check_type: &str, config: &Value, content: &str, artifacts_url: Option<&str>) -> Result<(), String> {
+    match check_type {
+        "json_schema" => {
+            let schema = config.get("schema").ok_or("Check is misconfigured")?;
+            let validator = jsonschema::validator_for(schema).map_err(|e| format!("Check is misconfigured: {e}"))?;
+            let instance: Value = serde_json::from_str(content).map_err(|e| format!("Content is not JSON: {e}"))?;
+            let errors: Vec<String> = validator.iter_errors(&instance).take(5).map(|e| e.to_string()).collect();
+            if errors.is_empty() { Ok(()) } else { Err(errors.join("; ")) }
+        }
+        "regex" => {
+            let pattern = config.get("pattern").and_then(Value::as_str).ok_or("Check is misconfigured")?;
+            if compile(pattern)?.is_match(content) {
+                Ok(())
+            } else {
+                Err(format!("Content does not match /{pattern}/"))
+            }
+        }
+        "max_length" => {
+            let max = config.get("max_chars").and_then(Value::as_u64).ok_or("Check is misconfigured")?;
+            let len = content.chars().count() as u64;
+            if len <= max {
+                Ok(())
+            } else {
+                Err(format!("Content is {len} characters, limit is {max}"))
+            }
+        }
+        "file_types" => {
+            let allowed = extensions(config);
+            let url = artifacts_url.ok_or("No artifacts_url given")?;
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+            if allowed.contains(&extension) {
+                Ok(())
+            } else {
+                Err(format!("Artifact must be one of: {}", allowed.join(", ")))
+            }
+        }
+        other => Err(format!("Unknown check type '{other}'")),
+    }
+}
+
+fn compile(pattern: &str) -> Result<regex::Regex, String> {
+    regex::RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {e}"))
+}
+
+/// Lower-cased extensions without the leading dot.
+fn extensions(config: &Value) -> Vec<String> {
+    config
+        .get("extensions")
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(Value::as_str)
+                .map(|e| e.trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn run_check_covers_each_check_type() {
+        let schema = json!({"schema": {
+            "type": "object",
+            "required": ["name"],
+            "properties": {"name": {"type": "string"}}
+        }});
+        let pattern = json!({"pattern": "^[A-Z]{3}-\\d+$"});
+        let max_chars = json!({"max_chars": 5});
+        let file_types = json!({"extensions": [".PDF", "md"]});
+
+        // (check type, config, content, artifacts_url, passes)
+        let cases = [
+            ("json_schema", &schema, r#"{"name": "lobster"}"#, None, true),
+            ("json_schema", &schema, r#"{"name": 7}"#, None, false),
+            ("json_schema", &schema, "{}", None, false),
+            ("json_schema", &schema, "not json", None, false),
+            ("regex", &pattern, "ABC-42", None, true),
+            ("regex", &pattern, "abc-42", None, false),
+            ("max_length", &max_chars, "12345", None, true),
+            // counted in characters, not bytes
+            ("max_length", &max_chars, "ééééé", None, true),
+            ("max_length", &max_chars, "123456", None, false),
+            ("file_types", &file_types, "", Some("https://cdn.example/report.pdf"), true),
+            ("file_types", &file_types, "", Some("https://cdn.example/README.MD?dl=1#top"), true),
+            ("file_types", &file_types, "", Some("https://cdn.example/report.docx"), false),
+            ("file_types", &file_types, "", Some("https://cdn.example/report"), false),
+            ("file_types", &file_types, "", None, false),
+            ("spellcheck", &max_chars, "12345", None, false),
+        ];
+        for (check_type, config, content, artifacts_url, passes) in cases {
+            let result = run_check(check_type, config, content, artifacts_url);
+            assert_eq!(result.is_ok(), passes, "{check_type} on {content:?} / {artifacts_url:?}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn run_check_reports_misconfigured_checks() {
+        for (check_type, config) in [
+            ("json_schema", json!({})),
+            ("regex", json!({"pattern": 7})),
+            ("regex", json!({"pattern": "("})),
+            ("max_length", json!({"max_chars": "ten"})),
+        ] {
+            assert!(run_check(check_type, &config, "content", None).is_err(), "{check_type} {config}");
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::*;
+use crate::services::{notifications, outbound};
+
+/// Where the endpoint method expects the challenge token, relative to `endpoint_url`.
+pub const WELL_KNOWN_PATH: &str = ".well-known/lobstermarket-verification.txt";
+/// How long a challenge can be completed.
+pub const CHALLENGE_TTL_HOURS: i64 = 24;
+/// How long a successful verification keeps the agent `verified`.
+pub const VALIDITY_DAYS: i64 = 90;
+/// Re-verification starts this long before `verified_until`.
+const RENEW_BEFORE_DAYS: i64 = 7;
+
+pub fn well_known_url(endpoint_url: &str) -> String {
+    format!("{}/{WELL_KNOWN_PATH}", endpoint_url.trim_end_matches('/'))
+}
+
+/// Fetch the well-known file and check that it contains `token`.
+pub async fn check_endpoint(http: &reqwest::Client, endpoint_url: &str, token: &str) -> Result<(), String> {
+    let url = well_known_url(endpoint_url);
+    outbound::check_url(&url).await?;
+
+    let resp = http
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach endpoint: {e}"))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Endpoint responded {}", resp.status()));
+    }
+
+    let body = resp.text().await.map_err(|e| format!("Could not read response: {e}"))?;
+    if body.lines().any(|line| line.trim() == token) {
+        Ok(())
+    } else {
+        Err("Challenge token not found in the well-known file".into())
+    }
+}
+
+/// Record a passed challenge and extend `verified_until`; the tier follows from `recompute_tier`.
+pub async fn mark_verified(db: &PgPool, verification: &AgentVerification, wallet: Option<&str>) -> AppResult<AgentVerification> {
+    let updated = sqlx::query_as::<_, AgentVerification>(
+        r#"UPDATE agent_verifications
+           SET status = 'verified', verified_at = now(), wallet = COALESCE($2, wallet),
+               last_error = NULL, reminder_sent_at = NULL
+           WHERE id = $1 RETURNING *"#,
+    )
+    .bind(verification.id)
+    .bind(wallet)
+    .fetch_one(db)
+    .await?;
+
+    sqlx::query(
+        "UPDATE agents SET verified_until = now() + make_interval(days => $2::int), updated_at = now() WHERE id = $1"
+    )
+    .bind(verification.agent_id)
+    .bind(VALIDITY_DAYS as i32)
+    .execute(db)
+    .await?;
+
+    recompute_tier(db, verification.agent_id).await?;
+
+    Ok(updated)
+}
+
+/// Derive an agent's tier: `verified` while ownership verification is current,
+/// `proved` if additionally its latest benchmark run passed in any declared capability.
+/// Notifies the owner when the tier changes.
+pub async fn recompute_tier(db: &PgPool, agent_id: Uuid) -> AppResult<()> {
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
+        .bind(agent_id)
+        .fetch_one(db)
+        .await?;
+
+    let passed: (bool,) = sqlx::query_as(
+        r#"SELECT EXISTS (
+               SELECT 1 FROM (
+                   SELECT DISTINCT ON (r.capability) r.passed
+                   FROM benchmark_runs r
+                   JOIN agent_capabilities c ON c.agent_id = r.agent_id AND c.capability = r.capability
+                   WHERE r.agent_id = $1 AND r.status = 'completed'
+                   ORDER BY r.capability, r.finished_at DESC
+               ) latest WHERE latest.passed
+           )"#,
+    )
+    .bind(agent_id)
+    .fetch_one(db)
+    .await?;
+
+    let verified = agent.verified_until.is_some_and(|t| t > Utc::now());
+    let tier = match (verified, passed.0) {
+        (true, true) => "proved",
+        (true, false) => "verified",
+        _ => "unverified",
+    };
+    if tier == agent.verification_tier {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE agents SET verification_tier = $1, updated_at = now() WHERE id = $2")
+        .bind(tier)
+        .bind(agent_id)
+        .execute(db)
+        .await?;
+
+    notifications::notify(
+        db,
+        agent.owner_id,
+        "verification_tier_changed",
+        &format!("\"{}\" is now {tier}", agent.name),
+        serde_json::json!({"agent_id": agent_id, "from": agent.verification_tier, "to": tier}),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Re-verification worker: expires stale challenges, silently renews endpoint
+/// verifications that still pass, asks wallet-verified owners to sign again, and
+/// drops lapsed agents back to `unverified`.
+pub async fn reverify_all(db: &PgPool, http: &reqwest::Client) {
+    // 1. Stale challenges
+    if let Err(e) = sqlx::query(
+        "UPDATE agent_verifications SET status = 'expired' WHERE status = 'pending' AND expires_at < now()"
+    )
+    .execute(db)
+    .await
+    {
+        tracing::warn!("Expiring stale verification challenges failed: {e}");
+    }
+
+    // 2. Verifications due for renewal: the latest passed challenge of each agent nearing expiry
+    let due = match sqlx::query_as::<_, AgentVerification>(
+        r#"SELECT DISTINCT ON (v.agent_id) v.* FROM agent_verifications v
+           JOIN agents a ON a.id = v.agent_id
+           WHERE v.status = 'verified' AND a.status = 'active'
+             AND a.verified_until < now() + make_interval(days => $1::int)
+           ORDER BY v.agent_id, v.verified_at DESC"#,
+    )
+    .bind(RENEW_BEFORE_DAYS as i32)
+    .fetch_all(db)
+    .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!("Re-verification scan failed: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut renewed = 0;
+    for verification in due {
+        match renew(db, http, &verification).await {
+            Ok(true) => renewed += 1,
+            Ok(false) => {}
+            Err(e) => tracing::warn!(agent_id = %verification.agent_id, "Re-verification failed: {e}"),
+        }
+    }
+
+    // 3. Lapsed (proved agents need current ownership verification too)
+    let lapsed: Vec<(Uuid, Uuid, String)> = match sqlx::query_as(
+        r#"UPDATE agents SET verification_tier = 'unverified', updated_at = now()
+           WHERE verification_tier IN ('verified', 'proved') AND verified_until < now()
+           RETURNING id, owner_id, name"#,
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(lapsed) => lapsed,
+        Err(e) => {
+            tracing::warn!("Lapsing expired verifications failed: {e}");
+            Vec::new()
+        }
+    };
+
+    for (agent_id, owner_id, name) in &lapsed {
+        notifications::notify(
+            db,
+            *owner_id,
+            "verification_lapsed",
+            &format!("\"{name}\" is no longer verified; request a new verification to restore the badge"),
+            serde_json::json!({"agent_id": agent_id}),
+        )
+        .await;
+    }
+
+    if renewed > 0 || !lapsed.is_empty() {
+        tracing::info!("Re-verified {renewed} agent(s), {} lapsed", lapsed.len());
+    }
+}
+
+/// Endpoint verifications are re-checked automatically; wallet ones need the owner.
+async fn renew(db: &PgPool, http: &reqwest::Client, verification: &AgentVerification) -> AppResult<bool> {
+    let agent = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
+        .bind(verification.agent_id)
+        .fetch_one(db)
+        .await?;
+
+    if verification.method == "endpoint" {
+        if let Some(endpoint_url) = &agent.endpoint_url {
+            if check_endpoint(http, endpoint_url, &verification.token).await.is_ok() {
+                mark_verified(db, verification, None).await?;
+                return Ok(true);
+            }
+        }
+    }
+
+    if verification.reminder_sent_at.is_some() {
+        return Ok(false);
+    }
+
+    notifications::notify(
+        db,
+        agent.owner_id,
+        "verification_expiring",
+        &format!(
+            "Verification of \"{}\" expires on {}; request a new verification to keep the badge",
+            agent.name,
+            agent.verified_until.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default()
+        ),
+        serde_json::json!({"agent_id": agent.id, "verified_until": agent.verified_until}),
+    )
+    .await;
+
+    sqlx::query("UPDATE agent_verifications SET reminder_sent_at = now() WHERE id = $1")
+        .bind(verification.id)
+        .execute(db)
+        .await?;
+
+    Ok(false)
+}
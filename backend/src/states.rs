@@ -0,0 +1,337 @@
+//! Lifecycle states for jobs, contracts, escrow, offers, submissions and milestones.
+//!
+//! Every state change goes through one of the `set_*` helpers below, which check
+//! the transition table and update the row only if it is still in the state that
+//! was checked. They run on the caller's connection so multi-step flows can share
+//! one transaction. Reads may still filter on the raw strings in SQL.
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Contract, EscrowAccount, Job, Milestone, Offer, Submission};
+
+/// A lifecycle with a fixed set of legal transitions.
+pub trait StateMachine: Copy + PartialEq + std::fmt::Display {
+    /// Entity name used in error messages.
+    const ENTITY: &'static str;
+
+    fn can_transition_to(self, next: Self) -> bool;
+
+    /// `Ok(next)` if `self → next` is legal, otherwise `AppError::InvalidTransition`.
+    fn transition(self, next: Self) -> AppResult<Self> {
+        if self.can_transition_to(next) {
+            Ok(next)
+        } else {
+            Err(AppError::InvalidTransition(format!(
+                "{} cannot move from '{}' to '{}'",
+                Self::ENTITY, self, next
+            )))
+        }
+    }
+}
+
+macro_rules! state_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $s:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+        #[serde(rename_all = "snake_case")]
+        #[sqlx(type_name = "varchar", rename_all = "snake_case")]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $s),+
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+state_enum!(
+    /// `jobs.state`
+    JobState {
+        Draft => "draft",
+        Open => "open",
+        Matched => "matched",
+        InProgress => "in_progress",
+        Submitted => "submitted",
+        Reviewing => "reviewing",
+        Completed => "completed",
+        Disputed => "disputed",
+        Resolved => "resolved",
+        Cancelled => "cancelled",
+    }
+);
+
+state_enum!(
+    /// `contracts.status`
+    ContractStatus {
+        Active => "active",
+        Completed => "completed",
+        Disputed => "disputed",
+        Resolved => "resolved",
+        Cancelled => "cancelled",
+    }
+);
+
+state_enum!(
+    /// `escrow_accounts.state`
+    EscrowState {
+        None => "none",
+        Funded => "funded",
+        Locked => "locked",
+        Frozen => "frozen",
+        Released => "released",
+        Refunded => "refunded",
+        Split => "split",
+    }
+);
+
+state_enum!(
+    /// `offers.status`
+    OfferStatus {
+        Pending => "pending",
+        Accepted => "accepted",
+        Rejected => "rejected",
+        Withdrawn => "withdrawn",
+        Expired => "expired",
+    }
+);
+
+state_enum!(
+    /// `submissions.status`
+    SubmissionStatus {
+        Pending => "pending",
+        Accepted => "accepted",
+        Rejected => "rejected",
+        RevisionRequested => "revision_requested",
+    }
+);
+
+state_enum!(
+    /// `milestones.state`
+    MilestoneState {
+        Pending => "pending",
+        Funded => "funded",
+        Submitted => "submitted",
+        Released => "released",
+    }
+);
+
+// ─── Transition table ───────────────────────────────────────
+
+impl StateMachine for JobState {
+    const ENTITY: &'static str = "Job";
+
+    fn can_transition_to(self, next: Self) -> bool {
+        use JobState::*;
+        matches!(
+            (self, next),
+            (Draft, Open | Cancelled)
+                | (Open, Matched | Reviewing | Cancelled)
+                // battle: winner picked straight from the open or closed field
+                | (Open | Reviewing, Completed)
+                | (Reviewing, Cancelled)
+                | (Matched, InProgress)
+                | (InProgress, Submitted | Disputed)
+                // milestone contracts complete without a contract-level submission
+                | (InProgress, Completed)
+                | (Submitted, InProgress | Completed | Disputed)
+                | (Disputed, Resolved)
+        )
+    }
+}
+
+impl StateMachine for ContractStatus {
+    const ENTITY: &'static str = "Contract";
+
+    fn can_transition_to(self, next: Self) -> bool {
+        use ContractStatus::*;
+        matches!(
+            (self, next),
+            (Active, Completed | Disputed | Cancelled) | (Disputed, Resolved)
+        )
+    }
+}
+
+impl StateMachine for EscrowState {
+    const ENTITY: &'static str = "Escrow";
+
+    fn can_transition_to(self, next: Self) -> bool {
+        use EscrowState::*;
+        matches!(
+            (self, next),
+            (None, Funded)
+                | (Funded, Locked | Frozen | Refunded)
+                | (Locked, Funded | Released | Frozen | Refunded)
+                | (Frozen, Released | Split | Refunded)
+        )
+    }
+}
+
+impl StateMachine for OfferStatus {
+    const ENTITY: &'static str = "Offer";
+
+    fn can_transition_to(self, next: Self) -> bool {
+        use OfferStatus::*;
+        matches!((self, next), (Pending, Accepted | Rejected | Withdrawn | Expired))
+    }
+}
+
+impl StateMachine for SubmissionStatus {
+    const ENTITY: &'static str = "Submission";
+
+    fn can_transition_to(self, next: Self) -> bool {
+        use SubmissionStatus::*;
+        matches!((self, next), (Pending, Accepted | Rejected | RevisionRequested))
+    }
+}
+
+impl StateMachine for MilestoneState {
+    const ENTITY: &'static str = "Milestone";
+
+    fn can_transition_to(self, next: Self) -> bool {
+        use MilestoneState::*;
+        matches!((self, next), (Pending, Funded) | (Funded, Submitted) | (Submitted, Released))
+    }
+}
+
+// ─── Persisted transitions ──────────────────────────────────
+
+fn changed_underneath(entity: &str) -> AppError {
+    AppError::Conflict(format!("{entity} was modified concurrently; retry"))
+}
+
+/// Move a job to `next`.
+pub async fn set_job_state(conn: &mut PgConnection, job: &Job, next: JobState) -> AppResult<Job> {
+    job.state.transition(next)?;
+
+    sqlx::query_as::<_, Job>(
+        "UPDATE jobs SET state = $1, updated_at = now() WHERE id = $2 AND state = $3 RETURNING *"
+    )
+    .bind(next)
+    .bind(job.id)
+    .bind(job.state)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| changed_underneath(JobState::ENTITY))
+}
+
+/// Lock a job row and move it to `next`.
+pub async fn set_job_state_by_id(conn: &mut PgConnection, job_id: Uuid, next: JobState) -> AppResult<Job> {
+    let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1 FOR UPDATE")
+        .bind(job_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+
+    set_job_state(conn, &job, next).await
+}
+
+/// Move a contract to `next`; stamps `completed_at` when it closes.
+pub async fn set_contract_status(conn: &mut PgConnection, contract: &Contract, next: ContractStatus) -> AppResult<Contract> {
+    contract.status.transition(next)?;
+
+    sqlx::query_as::<_, Contract>(
+        r#"UPDATE contracts
+           SET status = $1,
+               completed_at = CASE WHEN $1 IN ('completed', 'resolved') THEN now() ELSE completed_at END
+           WHERE id = $2 AND status = $3 RETURNING *"#,
+    )
+    .bind(next)
+    .bind(contract.id)
+    .bind(contract.status)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| changed_underneath(ContractStatus::ENTITY))
+}
+
+/// Move an escrow account to `next`, stamping the matching timestamp.
+/// Locking restarts the review window.
+pub async fn set_escrow_state(conn: &mut PgConnection, escrow: &EscrowAccount, next: EscrowState) -> AppResult<EscrowAccount> {
+    escrow.state.transition(next)?;
+
+    sqlx::query_as::<_, EscrowAccount>(
+        r#"UPDATE escrow_accounts
+           SET state = $1,
+               funded_at = CASE WHEN $3 = 'none' THEN now() ELSE funded_at END,
+               locked_at = CASE WHEN $1 = 'locked' THEN now() ELSE locked_at END,
+               reminder_sent_at = CASE WHEN $1 = 'locked' THEN NULL ELSE reminder_sent_at END,
+               released_at = CASE WHEN $1 IN ('released', 'refunded', 'split') THEN now() ELSE released_at END
+           WHERE id = $2 AND state = $3 RETURNING *"#,
+    )
+    .bind(next)
+    .bind(escrow.id)
+    .bind(escrow.state)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| changed_underneath(EscrowState::ENTITY))
+}
+
+/// Move an offer to `next`.
+pub async fn set_offer_status(conn: &mut PgConnection, offer: &Offer, next: OfferStatus) -> AppResult<Offer> {
+    offer.status.transition(next)?;
+
+    sqlx::query_as::<_, Offer>(
+        "UPDATE offers SET status = $1, updated_at = now() WHERE id = $2 AND status = $3 RETURNING *"
+    )
+    .bind(next)
+    .bind(offer.id)
+    .bind(offer.status)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| changed_underneath(OfferStatus::ENTITY))
+}
+
+/// Review a submission: move it to `next`, recording optional client feedback.
+pub async fn set_submission_status(
+    conn: &mut PgConnection,
+    submission: &Submission,
+    next: SubmissionStatus,
+    feedback: Option<&str>,
+) -> AppResult<Submission> {
+    submission.status.transition(next)?;
+
+    sqlx::query_as::<_, Submission>(
+        r#"UPDATE submissions SET status = $1, feedback = COALESCE($2, feedback), reviewed_at = now()
+           WHERE id = $3 AND status = $4 RETURNING *"#,
+    )
+    .bind(next)
+    .bind(feedback)
+    .bind(submission.id)
+    .bind(submission.status)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| changed_underneath(SubmissionStatus::ENTITY))
+}
+
+/// Move a milestone to `next`, stamping the matching timestamp.
+pub async fn set_milestone_state(conn: &mut PgConnection, milestone: &Milestone, next: MilestoneState) -> AppResult<Milestone> {
+    milestone.state.transition(next)?;
+
+    sqlx::query_as::<_, Milestone>(
+        r#"UPDATE milestones
+           SET state = $1,
+               funded_at = CASE WHEN $1 = 'funded' THEN now() ELSE funded_at END,
+               submitted_at = CASE WHEN $1 = 'submitted' THEN now() ELSE submitted_at END,
+               released_at = CASE WHEN $1 = 'released' THEN now() ELSE released_at END
+           WHERE id = $2 AND state = $3 RETURNING *"#,
+    )
+    .bind(next)
+    .bind(milestone.id)
+    .bind(milestone.state)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| changed_underneath(MilestoneState::ENTITY))
+}
@@ -0,0 +1,45 @@
+//! Agent API keys stop working with their owner's account.
+
+use axum::extract::{FromRequestParts, Path, State};
+use axum::http::Request;
+use axum::Json;
+
+use super::{claims, TestDb};
+use crate::auth::middleware::{AgentAuth, AuthUser};
+use crate::models::*;
+use crate::routes::agent_keys;
+
+#[tokio::test]
+async fn api_keys_stop_working_once_the_owner_is_suspended() {
+    let Some(db) = TestDb::create().await else { return };
+    let owner = db.user().await;
+    let agent = db.agent(owner).await;
+
+    let Json(new_key) = agent_keys::create_key(
+        State(db.state.clone()),
+        AuthUser(claims(owner)),
+        Path(agent),
+        Json(CreateApiKeyReq { name: "ci".into() }),
+    )
+    .await
+    .unwrap();
+
+    let authenticate = || async {
+        let (mut parts, ()) = Request::builder()
+            .header("authorization", format!("Bearer {}", new_key.key))
+            .body(())
+            .unwrap()
+            .into_parts();
+        AgentAuth::from_request_parts(&mut parts, &db.state).await
+    };
+
+    let AgentAuth(caller) = authenticate().await.unwrap();
+    assert_eq!((caller.user_id, caller.agent_id), (owner, Some(agent)));
+
+    sqlx::query("UPDATE users SET is_suspended = true WHERE id = $1")
+        .bind(owner)
+        .execute(db.db())
+        .await
+        .unwrap();
+    assert!(authenticate().await.is_err(), "a suspended owner's keys are rejected");
+}
@@ -0,0 +1,156 @@
+//! Races on the money paths: concurrent requests must leave exactly one winner
+//! and never double-write the escrow ledger.
+
+use axum::extract::{Path, State};
+use axum::Json;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::{claims, TestDb};
+use crate::auth::middleware::AuthUser;
+use crate::models::*;
+use crate::routes::offers;
+use crate::states::{self, JobState};
+
+const RACERS: usize = 8;
+
+/// Await tasks that were all spawned before the first one is awaited.
+async fn join_all<T>(tasks: impl Iterator<Item = JoinHandle<T>>) -> Vec<T> {
+    let handles: Vec<_> = tasks.collect();
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.expect("task panicked"));
+    }
+    results
+}
+
+async fn count(db: &TestDb, sql: &str, id: Uuid) -> i64 {
+    let (n,): (i64,) = sqlx::query_as(sql).bind(id).fetch_one(db.db()).await.unwrap();
+    n
+}
+
+/// A client, an open job and one pending offer from each of `agents` agents.
+async fn job_with_offers(db: &TestDb, agents: usize) -> (Uuid, Uuid, Vec<Uuid>) {
+    let client = db.user().await;
+    let job = db.open_job(client).await;
+    let mut offer_ids = Vec::new();
+    for i in 0..agents {
+        let owner = db.user().await;
+        let agent = db.agent(owner).await;
+        offer_ids.push(db.offer(job, agent, 1000 + i as i64).await);
+    }
+    (client, job, offer_ids)
+}
+
+async fn accept(db: &TestDb, client: Uuid, offer_id: Uuid) -> Contract {
+    let Json(contract) = offers::accept_offer(State(db.state.clone()), AuthUser(claims(client)), Path(offer_id))
+        .await
+        .unwrap();
+    contract
+}
+
+async fn fund_concurrently(db: &TestDb, client: Uuid, contract_id: Uuid) -> usize {
+    let tasks = (0..RACERS).map(|_| {
+        let state = db.state.clone();
+        tokio::spawn(async move {
+            crate::escrow_fund(State(state), AuthUser(claims(client)), Json(EscrowFundReq { contract_id })).await
+        })
+    });
+    join_all(tasks).await.into_iter().filter(|r| r.is_ok()).count()
+}
+
+#[tokio::test]
+async fn concurrent_accepts_create_one_contract() {
+    let Some(db) = TestDb::create().await else { return };
+    let (client, job, offer_ids) = job_with_offers(&db, 4).await;
+
+    // Every offer twice, all at once
+    let tasks = offer_ids.iter().chain(&offer_ids).map(|&offer_id| {
+        let state = db.state.clone();
+        tokio::spawn(async move { offers::accept_offer(State(state), AuthUser(claims(client)), Path(offer_id)).await })
+    });
+    let accepted: Vec<Contract> = join_all(tasks)
+        .await
+        .into_iter()
+        .filter_map(|r| r.ok().map(|Json(c)| c))
+        .collect();
+
+    assert_eq!(accepted.len(), 1, "exactly one accept must win");
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM contracts WHERE job_id = $1", job).await, 1);
+    assert_eq!(
+        count(&db, "SELECT COUNT(*) FROM escrow_accounts e JOIN contracts c ON c.id = e.contract_id WHERE c.job_id = $1", job).await,
+        1
+    );
+
+    let statuses: Vec<(Uuid, String)> = sqlx::query_as("SELECT id, status FROM offers WHERE job_id = $1")
+        .bind(job)
+        .fetch_all(db.db())
+        .await
+        .unwrap();
+    for (id, status) in statuses {
+        let expected = if id == accepted[0].offer_id { "accepted" } else { "rejected" };
+        assert_eq!(status, expected, "offer {id}");
+    }
+
+    let (state,): (String,) = sqlx::query_as("SELECT state FROM jobs WHERE id = $1")
+        .bind(job)
+        .fetch_one(db.db())
+        .await
+        .unwrap();
+    assert_eq!(state, "matched");
+}
+
+#[tokio::test]
+async fn concurrent_funds_fund_once() {
+    let Some(db) = TestDb::create().await else { return };
+    let (client, job, offer_ids) = job_with_offers(&db, 1).await;
+    let contract = accept(&db, client, offer_ids[0]).await;
+
+    assert_eq!(fund_concurrently(&db, client, contract.id).await, 1, "exactly one fund must win");
+    assert_eq!(
+        count(&db, "SELECT COUNT(*) FROM escrow_ledger_entries l JOIN escrow_accounts e ON e.id = l.escrow_id WHERE e.contract_id = $1 AND l.entry_type = 'fund'", contract.id).await,
+        1
+    );
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM job_dispatches WHERE contract_id = $1", contract.id).await, 1);
+
+    let (state,): (String,) = sqlx::query_as("SELECT state FROM jobs WHERE id = $1")
+        .bind(job)
+        .fetch_one(db.db())
+        .await
+        .unwrap();
+    assert_eq!(state, "in_progress");
+}
+
+#[tokio::test]
+async fn concurrent_releases_pay_once() {
+    let Some(db) = TestDb::create().await else { return };
+    let (client, job, offer_ids) = job_with_offers(&db, 1).await;
+    let contract = accept(&db, client, offer_ids[0]).await;
+    assert_eq!(fund_concurrently(&db, client, contract.id).await, 1);
+
+    // Work submitted: escrow locked for review
+    let mut tx = db.db().begin().await.unwrap();
+    crate::services::escrow::lock_escrow(&mut tx, contract.id).await.unwrap();
+    states::set_job_state_by_id(&mut tx, job, JobState::Submitted).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let tasks = (0..RACERS).map(|_| {
+        let state = db.state.clone();
+        let contract_id = contract.id;
+        tokio::spawn(async move {
+            crate::escrow_release(State(state), AuthUser(claims(client)), Json(EscrowReleaseReq { contract_id })).await
+        })
+    });
+    let released = join_all(tasks).await.into_iter().filter(|r| r.is_ok()).count();
+
+    assert_eq!(released, 1, "exactly one release must win");
+    assert_eq!(
+        count(&db, "SELECT COUNT(*) FROM escrow_ledger_entries l JOIN escrow_accounts e ON e.id = l.escrow_id WHERE e.contract_id = $1 AND l.entry_type = 'release'", contract.id).await,
+        1
+    );
+    assert_eq!(
+        count(&db, "SELECT total_jobs_completed::BIGINT FROM agents WHERE id = $1", contract.agent_id).await,
+        1,
+        "agent credited once"
+    );
+}
@@ -0,0 +1,142 @@
+//! Escrow flows that must not pay out money the client never put in.
+
+use axum::extract::{Path, State};
+use axum::Json;
+use uuid::Uuid;
+
+use super::{claims, TestDb};
+use crate::auth::middleware::AuthUser;
+use crate::models::*;
+use crate::routes::{contracts, offers};
+use crate::states::{self, JobState};
+
+async fn ledger_total(db: &TestDb, contract_id: Uuid, entry_type: &str) -> i64 {
+    let (total,): (Option<i64>,) = sqlx::query_as(
+        r#"SELECT SUM(l.amount_lamports)::BIGINT FROM escrow_ledger_entries l
+           JOIN escrow_accounts e ON e.id = l.escrow_id
+           WHERE e.contract_id = $1 AND l.entry_type = $2"#,
+    )
+    .bind(contract_id)
+    .bind(entry_type)
+    .fetch_one(db.db())
+    .await
+    .unwrap();
+    total.unwrap_or(0)
+}
+
+#[tokio::test]
+async fn amendment_top_up_is_funded_by_the_client_before_release() {
+    let Some(db) = TestDb::create().await else { return };
+    let client = db.user().await;
+    let job = db.open_job(client).await;
+    let owner = db.user().await;
+    let agent = db.agent(owner).await;
+    let offer = db.offer(job, agent, 1000).await;
+
+    let Json(contract) = offers::accept_offer(State(db.state.clone()), AuthUser(claims(client)), Path(offer))
+        .await
+        .unwrap();
+    let Json(funded) =
+        crate::escrow_fund(State(db.state.clone()), AuthUser(claims(client)), Json(EscrowFundReq { contract_id: contract.id }))
+            .await
+            .unwrap();
+    assert_eq!(funded.amount_lamports, 1000);
+
+    // Client offers 500 more, the agent's owner accepts
+    let Json(amendment) = contracts::propose_amendment(
+        State(db.state.clone()),
+        AuthUser(claims(client)),
+        Path(contract.id),
+        Json(AmendmentReq { additional_revisions: 0, additional_price_lamports: Some(500) }),
+    )
+    .await
+    .unwrap();
+    let Json(amended) =
+        contracts::accept_amendment(State(db.state.clone()), AuthUser(claims(owner)), Path((contract.id, amendment.id)))
+            .await
+            .unwrap();
+    assert_eq!(amended.agreed_price_lamports, 1500);
+    assert_eq!(ledger_total(&db, contract.id, "fund").await, 1000, "accepting must not record funding");
+
+    let mut tx = db.db().begin().await.unwrap();
+    crate::services::escrow::lock_escrow(&mut tx, contract.id).await.unwrap();
+    states::set_job_state_by_id(&mut tx, job, JobState::Submitted).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let release = |user| {
+        crate::escrow_release(State(db.state.clone()), AuthUser(claims(user)), Json(EscrowReleaseReq { contract_id: contract.id }))
+    };
+    let fund_top_up = |user| {
+        crate::escrow_fund_top_up(State(db.state.clone()), AuthUser(claims(user)), Json(EscrowFundReq { contract_id: contract.id }))
+    };
+
+    assert!(release(client).await.is_err(), "release waits for the top-up");
+    assert!(fund_top_up(owner).await.is_err(), "only the client funds");
+
+    let Json(escrow) = fund_top_up(client).await.unwrap();
+    assert_eq!((escrow.amount_lamports, escrow.topup_due_lamports), (1500, 0));
+    assert!(fund_top_up(client).await.is_err(), "nothing left to fund");
+
+    let Json(released) = release(client).await.unwrap();
+    assert_eq!(released.state, states::EscrowState::Released);
+    assert_eq!(ledger_total(&db, contract.id, "fund").await, 1500);
+    assert_eq!(ledger_total(&db, contract.id, "release").await, 1500);
+}
+
+#[tokio::test]
+async fn auto_release_cancels_an_unfunded_top_up() {
+    let Some(db) = TestDb::create().await else { return };
+    let client = db.user().await;
+    let job = db.open_job(client).await;
+    let owner = db.user().await;
+    let agent = db.agent(owner).await;
+    let offer = db.offer(job, agent, 1000).await;
+
+    let Json(contract) = offers::accept_offer(State(db.state.clone()), AuthUser(claims(client)), Path(offer))
+        .await
+        .unwrap();
+    let Json(funded) =
+        crate::escrow_fund(State(db.state.clone()), AuthUser(claims(client)), Json(EscrowFundReq { contract_id: contract.id }))
+            .await
+            .unwrap();
+    assert_eq!(funded.funded_lamports(), 1000);
+
+    let Json(amendment) = contracts::propose_amendment(
+        State(db.state.clone()),
+        AuthUser(claims(client)),
+        Path(contract.id),
+        Json(AmendmentReq { additional_revisions: 0, additional_price_lamports: Some(500) }),
+    )
+    .await
+    .unwrap();
+    let Json(amended) =
+        contracts::accept_amendment(State(db.state.clone()), AuthUser(claims(owner)), Path((contract.id, amendment.id)))
+            .await
+            .unwrap();
+    assert_eq!(amended.agreed_price_lamports, 1500);
+
+    let mut tx = db.db().begin().await.unwrap();
+    crate::services::escrow::lock_escrow(&mut tx, contract.id).await.unwrap();
+    states::set_job_state_by_id(&mut tx, job, JobState::Submitted).await.unwrap();
+    tx.commit().await.unwrap();
+
+    // A zero-hour window has already lapsed; the client never funds the top-up
+    crate::services::auto_release::run_review_window(db.db(), 0, 0).await;
+
+    let escrow = sqlx::query_as::<_, EscrowAccount>("SELECT * FROM escrow_accounts WHERE contract_id = $1")
+        .bind(contract.id)
+        .fetch_one(db.db())
+        .await
+        .unwrap();
+    assert_eq!(escrow.state, states::EscrowState::Released);
+    assert_eq!((escrow.amount_lamports, escrow.topup_due_lamports), (1000, 0));
+    assert_eq!(ledger_total(&db, contract.id, "release").await, 1000);
+    assert_eq!(ledger_total(&db, contract.id, "cancel_topup").await, 500);
+
+    let (price,): (i64,) = sqlx::query_as("SELECT agreed_price_lamports FROM contracts WHERE id = $1")
+        .bind(contract.id)
+        .fetch_one(db.db())
+        .await
+        .unwrap();
+    assert_eq!(price, 1000);
+}
@@ -0,0 +1,46 @@
+//! Who may see a private job.
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use super::{claims, TestDb};
+use crate::auth::middleware::{AuthUser, OptionalAuth};
+use crate::models::*;
+use crate::routes::{battle, judging};
+
+#[tokio::test]
+async fn invited_judges_see_a_private_battle() {
+    let Some(db) = TestDb::create().await else { return };
+    let client = db.user().await;
+    let judge = db.user().await;
+    let outsider = db.user().await;
+    let job = db.open_job(client).await;
+    sqlx::query("UPDATE jobs SET battle_mode = true, visibility = 'private' WHERE id = $1")
+        .bind(job)
+        .execute(db.db())
+        .await
+        .unwrap();
+
+    let view = |user| battle::get_battle(State(db.state.clone()), OptionalAuth(Some(claims(user))), Path(job));
+    let rubric = |user| judging::get_rubric(State(db.state.clone()), OptionalAuth(Some(claims(user))), Path(job));
+
+    assert!(view(judge).await.is_err(), "not a judge yet");
+
+    let Json(invited) = judging::invite_judge(
+        State(db.state.clone()),
+        AuthUser(claims(client)),
+        Path(job),
+        Json(InviteJudgeReq { user_id: judge }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(invited.user_id, judge);
+
+    let Json(battle) = view(judge).await.unwrap();
+    assert_eq!(battle.job.id, job);
+    let Json(rubric_view) = rubric(judge).await.unwrap();
+    assert_eq!(rubric_view.judges.len(), 1);
+
+    assert!(view(outsider).await.is_err());
+    assert!(rubric(outsider).await.is_err());
+}
@@ -0,0 +1,143 @@
+//! Postgres-backed tests. Each test gets a throwaway database loaded from
+//! `docker/init.sql`; set `TEST_DATABASE_URL` to a server the tests may create
+//! databases on, otherwise they are skipped.
+
+mod agent_keys;
+mod concurrency;
+mod escrow;
+mod invites;
+mod negotiation;
+
+use std::sync::Arc;
+
+use sqlx::{Connection, PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::auth::jwt::Claims;
+use crate::config::Config;
+use crate::AppState;
+
+const SCHEMA: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../docker/init.sql"));
+
+pub struct TestDb {
+    pub state: AppState,
+    admin_url: String,
+    name: String,
+}
+
+impl TestDb {
+    /// Create and migrate a fresh database, or `None` when `TEST_DATABASE_URL` is unset.
+    pub async fn create() -> Option<Self> {
+        let Ok(admin_url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL not set; skipping database test");
+            return None;
+        };
+
+        let name = format!("lm_test_{}", Uuid::new_v4().simple());
+        let mut admin = PgConnection::connect(&admin_url).await.expect("connect to TEST_DATABASE_URL");
+        sqlx::query(&format!("CREATE DATABASE {name}"))
+            .execute(&mut admin)
+            .await
+            .expect("create test database");
+        admin.close().await.ok();
+
+        let url = database_url(&admin_url, &name);
+        let db = crate::db::create_pool(&url).await;
+        sqlx::raw_sql(SCHEMA).execute(&db).await.expect("load docker/init.sql");
+
+        let config = Config { database_url: url, ..Config::from_env() };
+        let state = AppState {
+            db,
+            redis: redis::Client::open(config.redis_url.clone()).expect("Invalid Redis URL"),
+            config: Arc::new(config),
+        };
+        Some(Self { state, admin_url, name })
+    }
+
+    pub fn db(&self) -> &PgPool {
+        &self.state.db
+    }
+
+    pub async fn user(&self) -> Uuid {
+        let (id,): (Uuid,) = sqlx::query_as("INSERT INTO users (display_name) VALUES ('test') RETURNING id")
+            .fetch_one(self.db())
+            .await
+            .unwrap();
+        id
+    }
+
+    pub async fn agent(&self, owner_id: Uuid) -> Uuid {
+        let (id,): (Uuid,) = sqlx::query_as(
+            r#"INSERT INTO agents (owner_id, name, endpoint_url, dispatch_secret)
+               VALUES ($1, 'test agent', 'https://agent.example/run', 'test-secret') RETURNING id"#,
+        )
+        .bind(owner_id)
+        .fetch_one(self.db())
+        .await
+        .unwrap();
+        id
+    }
+
+    pub async fn open_job(&self, client_id: Uuid) -> Uuid {
+        let (id,): (Uuid,) = sqlx::query_as(
+            r#"INSERT INTO jobs (client_id, title, description, budget_lamports, state)
+               VALUES ($1, 'test job', 'test', 1000, 'open') RETURNING id"#,
+        )
+        .bind(client_id)
+        .fetch_one(self.db())
+        .await
+        .unwrap();
+        id
+    }
+
+    pub async fn offer(&self, job_id: Uuid, agent_id: Uuid, price: i64) -> Uuid {
+        let (id,): (Uuid,) = sqlx::query_as(
+            "INSERT INTO offers (job_id, agent_id, proposed_price_lamports) VALUES ($1, $2, $3) RETURNING id"
+        )
+        .bind(job_id)
+        .bind(agent_id)
+        .bind(price)
+        .fetch_one(self.db())
+        .await
+        .unwrap();
+        id
+    }
+}
+
+/// Drops the database even when the test panicked. Runs on its own thread and
+/// runtime: the test's runtime may be shutting down.
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let admin_url = self.admin_url.clone();
+        let sql = format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", self.name);
+        let dropped = std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async {
+                let mut admin = PgConnection::connect(&admin_url).await?;
+                sqlx::query(&sql).execute(&mut admin).await
+            })
+        })
+        .join();
+        if !matches!(dropped, Ok(Ok(_))) {
+            eprintln!("failed to drop test database {}", self.name);
+        }
+    }
+}
+
+/// Replace the database name in a Postgres URL.
+fn database_url(url: &str, name: &str) -> String {
+    let (base, query) = url.split_once('?').map_or((url, None), |(b, q)| (b, Some(q)));
+    let base = match base.rfind('/') {
+        Some(i) if i > base.find("://").map_or(0, |s| s + 2) => &base[..i],
+        _ => base,
+    };
+    match query {
+        Some(q) => format!("{base}/{name}?{q}"),
+        None => format!("{base}/{name}"),
+    }
+}
+
+pub fn claims(user_id: Uuid) -> Claims {
+    let now = chrono::Utc::now().timestamp();
+    Claims { sub: user_id, wallet: String::new(), role: "user".into(), exp: now + 3600, iat: now }
+}
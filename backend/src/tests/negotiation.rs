@@ -0,0 +1,72 @@
+//! Negotiation threads: the client can always get back to the agent's terms.
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use super::{claims, TestDb};
+use crate::auth::middleware::{AgentAuth, AgentCaller, AuthUser};
+use crate::models::*;
+use crate::routes::{negotiation, offers};
+
+#[tokio::test]
+async fn withdrawing_a_counter_proposal_reinstates_the_agents_terms() {
+    let Some(db) = TestDb::create().await else { return };
+    let client = db.user().await;
+    let job = db.open_job(client).await;
+    let owner = db.user().await;
+    let agent = db.agent(owner).await;
+    let offer = db.offer(job, agent, 1000).await;
+    sqlx::query(
+        r#"INSERT INTO offer_revisions (offer_id, version, proposed_by, proposer_role, price_lamports, max_revisions)
+           VALUES ($1, 1, $2, 'agent', 1000, 2)"#,
+    )
+    .bind(offer)
+    .bind(owner)
+    .execute(db.db())
+    .await
+    .unwrap();
+
+    let as_client = || AgentAuth(AgentCaller { user_id: client, agent_id: None });
+    let counter = |price| {
+        negotiation::propose_revision(
+            State(db.state.clone()),
+            as_client(),
+            Path(offer),
+            Json(ProposeRevisionReq {
+                price_lamports: Some(price),
+                estimated_duration_hours: None,
+                max_revisions: None,
+                scope: None,
+                message: None,
+            }),
+        )
+    };
+    let withdraw = |version| negotiation::withdraw_revision(State(db.state.clone()), as_client(), Path((offer, version)));
+    let as_owner = AgentAuth(AgentCaller { user_id: owner, agent_id: None });
+
+    let Json(v2) = counter(800).await.unwrap();
+    assert_eq!(v2.version, 2);
+    assert!(
+        offers::accept_offer(State(db.state.clone()), AuthUser(claims(client)), Path(offer)).await.is_err(),
+        "the client's own proposal waits for the agent"
+    );
+    assert!(
+        negotiation::withdraw_revision(State(db.state.clone()), as_owner, Path((offer, 2))).await.is_err(),
+        "only the client withdraws"
+    );
+
+    let Json(current) = withdraw(2).await.unwrap();
+    assert_eq!((current.version, current.status.as_str()), (1, "proposed"));
+    assert!(withdraw(2).await.is_err(), "already withdrawn");
+
+    // A later proposal still gets a fresh version number
+    let Json(v3) = counter(900).await.unwrap();
+    assert_eq!(v3.version, 3);
+    let Json(current) = withdraw(3).await.unwrap();
+    assert_eq!((current.version, current.status.as_str()), (1, "proposed"));
+
+    let Json(contract) = offers::accept_offer(State(db.state.clone()), AuthUser(claims(client)), Path(offer))
+        .await
+        .unwrap();
+    assert_eq!((contract.agreed_price_lamports, contract.terms_version), (1000, Some(1)));
+}
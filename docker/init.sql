@@ -36,17 +36,109 @@ CREATE TABLE agents (
     description         TEXT,
     avatar_url          VARCHAR(500),
     endpoint_url        VARCHAR(500),
+    dispatch_secret     VARCHAR(64),   -- HMAC key for signed job dispatches; shown to the owner only
     source_url          VARCHAR(500),
     verification_tier   VARCHAR(20) NOT NULL DEFAULT 'unverified', -- unverified | verified | proved
+    verified_until      TIMESTAMPTZ,   -- 'verified' tier lapses after this unless re-verified
     lobster_score       REAL NOT NULL DEFAULT 50.0,
     total_jobs_completed INT NOT NULL DEFAULT 0,
     on_time_pct         REAL NOT NULL DEFAULT 100.0,
+    battle_rating       REAL NOT NULL DEFAULT 1500.0,  -- multi-player Elo over decided battles
+    battles_played      INT NOT NULL DEFAULT 0,
     status              VARCHAR(20) NOT NULL DEFAULT 'active',     -- active | suspended | inactive
     created_at          TIMESTAMPTZ NOT NULL DEFAULT now(),
     updated_at          TIMESTAMPTZ NOT NULL DEFAULT now()
 );
 CREATE INDEX idx_agents_owner ON agents(owner_id);
 CREATE INDEX idx_agents_score ON agents(lobster_score DESC);
+CREATE INDEX idx_agents_battle_rating ON agents(battle_rating DESC) WHERE battles_played > 0;
+
+-- ─── Agent API keys ─────────────────────────────────────────
+-- Machine credentials: only the SHA-256 of the key is stored; `prefix` identifies it
+CREATE TABLE agent_api_keys (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id     UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    name         VARCHAR(100) NOT NULL,
+    prefix       VARCHAR(16) NOT NULL UNIQUE,
+    key_hash     VARCHAR(64) NOT NULL,
+    last_used_at TIMESTAMPTZ,
+    revoked_at   TIMESTAMPTZ,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_agent_api_keys_agent ON agent_api_keys(agent_id);
+
+-- ─── Agent health checks ────────────────────────────────────
+-- Samples from the endpoint prober; kept for 30 days
+CREATE TABLE agent_health_checks (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id    UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    is_up       BOOLEAN NOT NULL,
+    latency_ms  INT,
+    status_code INT,
+    error       TEXT,
+    checked_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_agent_health_checks_agent ON agent_health_checks(agent_id, checked_at DESC);
+
+-- ─── Agent verifications ────────────────────────────────────
+-- Ownership challenges: token served from endpoint_url or signed by the owner's wallet
+CREATE TABLE agent_verifications (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id    UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    method      VARCHAR(20) NOT NULL,  -- endpoint | wallet
+    token       VARCHAR(64) NOT NULL,
+    status      VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | verified | failed | expired
+    wallet      VARCHAR(64),           -- signing wallet, for the wallet method
+    last_error  TEXT,
+    expires_at  TIMESTAMPTZ NOT NULL,  -- challenge must be completed before this
+    verified_at TIMESTAMPTZ,
+    reminder_sent_at TIMESTAMPTZ,      -- owner asked to renew before verified_until lapses
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_agent_verifications_agent ON agent_verifications(agent_id, created_at DESC);
+
+-- ─── Benchmarks ─────────────────────────────────────────────
+-- Admin-defined evaluation tasks per capability; passing runs earn the 'proved' tier
+CREATE TABLE benchmark_tasks (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    capability      VARCHAR(100) NOT NULL,
+    name            VARCHAR(200) NOT NULL,
+    input           JSONB NOT NULL,
+    expected_output JSONB NOT NULL,
+    scoring         VARCHAR(20) NOT NULL DEFAULT 'exact', -- exact | contains | json_subset | numeric
+    tolerance       DOUBLE PRECISION,                     -- numeric scoring only
+    weight          REAL NOT NULL DEFAULT 1.0,
+    is_active       BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by      UUID REFERENCES users(id),
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_benchmark_tasks_capability ON benchmark_tasks(capability) WHERE is_active;
+
+CREATE TABLE benchmark_runs (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id     UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    capability   VARCHAR(100) NOT NULL,
+    status       VARCHAR(20) NOT NULL DEFAULT 'running', -- running | completed | failed
+    score        REAL,            -- weighted 0-100
+    passed       BOOLEAN,
+    task_count   INT NOT NULL DEFAULT 0,
+    started_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at  TIMESTAMPTZ
+);
+CREATE INDEX idx_benchmark_runs_agent ON benchmark_runs(agent_id, capability, started_at DESC);
+CREATE UNIQUE INDEX idx_benchmark_runs_running ON benchmark_runs(agent_id, capability) WHERE status = 'running';
+
+CREATE TABLE benchmark_results (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id      UUID NOT NULL REFERENCES benchmark_runs(id) ON DELETE CASCADE,
+    task_id     UUID NOT NULL REFERENCES benchmark_tasks(id),
+    output      JSONB,
+    score       REAL NOT NULL,    -- 0-1
+    latency_ms  INT,
+    error       TEXT,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_benchmark_results_run ON benchmark_results(run_id);
 
 -- ─── Agent capabilities ─────────────────────────────────────
 CREATE TABLE agent_capabilities (
@@ -68,23 +160,93 @@ CREATE TABLE jobs (
     -- draft | open | matched | in_progress | submitted | reviewing | completed | disputed | resolved | cancelled
     battle_mode            BOOLEAN NOT NULL DEFAULT FALSE,
     battle_max_submissions INT DEFAULT 3,
-    battle_partial_reward_pct INT DEFAULT 0,
+    battle_partial_reward_pct INT DEFAULT 0,  -- share of the prize split among ranked runners-up
+    battle_closes_at       TIMESTAMPTZ,   -- submissions stay sealed and are accepted until then
     deadline               TIMESTAMPTZ,
     currency               VARCHAR(10) NOT NULL DEFAULT 'USDC',  -- USDC | USDT | SOL
     currency_chain         VARCHAR(20) NOT NULL DEFAULT 'solana', -- solana | ethereum | base | tron | bnb
     tags                   TEXT[] DEFAULT '{}',
+    visibility             VARCHAR(20) NOT NULL DEFAULT 'public', -- public | invite_only (unlisted, readable via link, invited agents bid) | private (client and invitees only)
+    edited_at              TIMESTAMPTZ,   -- last PATCH; see job_revisions
     created_at             TIMESTAMPTZ NOT NULL DEFAULT now(),
     updated_at             TIMESTAMPTZ NOT NULL DEFAULT now()
 );
 CREATE INDEX idx_jobs_client ON jobs(client_id);
 CREATE INDEX idx_jobs_state ON jobs(state);
+CREATE INDEX idx_jobs_battle_closes ON jobs(battle_closes_at) WHERE battle_mode AND state = 'open';
+
+-- ─── Job revisions ──────────────────────────────────────────
+-- Edit history: each PATCH records the changed fields as {field: {from, to}}.
+CREATE TABLE job_revisions (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id       UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    revision     INT NOT NULL,
+    edited_by    UUID NOT NULL REFERENCES users(id),
+    changes      JSONB NOT NULL,
+    is_material  BOOLEAN NOT NULL DEFAULT FALSE,  -- description, budget or deadline changed
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(job_id, revision)
+);
+
+-- ─── Job invites ────────────────────────────────────────────
+-- Agents invited to an invite_only or private job; only they may bid on it.
+CREATE TABLE job_invites (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id      UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    agent_id    UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    invited_by  UUID NOT NULL REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(job_id, agent_id)
+);
+CREATE INDEX idx_job_invites_agent ON job_invites(agent_id);
+
+-- ─── Job templates & schedules ──────────────────────────────
+-- Saved job settings; deadlines are relative so a template can be reused.
+CREATE TABLE job_templates (
+    id                        UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_id                  UUID NOT NULL REFERENCES users(id),
+    name                      VARCHAR(100) NOT NULL,
+    title                     VARCHAR(300) NOT NULL,
+    description               TEXT NOT NULL,
+    budget_lamports           BIGINT,
+    currency                  VARCHAR(10) NOT NULL DEFAULT 'USDC',
+    currency_chain            VARCHAR(20) NOT NULL DEFAULT 'solana',
+    battle_mode               BOOLEAN NOT NULL DEFAULT FALSE,
+    battle_max_submissions    INT,
+    battle_partial_reward_pct INT,
+    battle_duration_hours     INT,           -- battle_closes_at = creation + this
+    deadline_hours            INT,           -- deadline = creation + this
+    tags                      TEXT[] DEFAULT '{}',
+    visibility                VARCHAR(20) NOT NULL DEFAULT 'public',
+    requirements              JSONB NOT NULL DEFAULT '[]', -- [{requirement, is_mandatory, check_type, check_config}]
+    created_at                TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_job_templates_owner ON job_templates(owner_id);
+
+-- Recurring jobs: a worker creates (and optionally publishes) a job from the template on each cron tick.
+CREATE TABLE job_schedules (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id   UUID NOT NULL REFERENCES job_templates(id) ON DELETE CASCADE,
+    owner_id      UUID NOT NULL REFERENCES users(id),
+    cron          VARCHAR(100) NOT NULL,   -- 5-field cron in UTC, or @hourly | @daily | @weekly | @monthly
+    auto_publish  BOOLEAN NOT NULL DEFAULT FALSE,
+    is_active     BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at   TIMESTAMPTZ,
+    last_run_at   TIMESTAMPTZ,
+    last_job_id   UUID REFERENCES jobs(id) ON DELETE SET NULL,
+    last_error    TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_job_schedules_due ON job_schedules(next_run_at) WHERE is_active;
 
 -- ─── Job requirements ───────────────────────────────────────
 CREATE TABLE job_requirements (
     id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
     job_id       UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
     requirement  TEXT NOT NULL,
-    is_mandatory BOOLEAN NOT NULL DEFAULT TRUE
+    is_mandatory BOOLEAN NOT NULL DEFAULT TRUE,
+    check_type   VARCHAR(20),   -- json_schema | regex | max_length | file_types; NULL = judged by people
+    check_config JSONB          -- {schema} | {pattern} | {max_chars} | {extensions[]}
 );
 
 -- ─── Offers ─────────────────────────────────────────────────
@@ -95,12 +257,36 @@ CREATE TABLE offers (
     proposed_price_lamports BIGINT,
     estimated_duration_hours INT,
     pitch                   TEXT,
-    status                  VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | accepted | rejected | withdrawn
+    max_revisions           INT NOT NULL DEFAULT 2,
+    status                  VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | accepted | rejected | withdrawn | expired
+    expires_at              TIMESTAMPTZ,   -- pending offers past this are expired by a worker
     created_at              TIMESTAMPTZ NOT NULL DEFAULT now(),
     updated_at              TIMESTAMPTZ NOT NULL DEFAULT now()
 );
 CREATE INDEX idx_offers_job ON offers(job_id);
 CREATE INDEX idx_offers_agent ON offers(agent_id);
+CREATE INDEX idx_offers_expiry ON offers(expires_at) WHERE status = 'pending';
+
+-- ─── Offer revisions ────────────────────────────────────────
+-- Negotiation thread: version 1 is the agent's original offer, later versions are
+-- counter-proposals from either side. Agreeing copies the terms onto the offer.
+CREATE TABLE offer_revisions (
+    id                       UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    offer_id                 UUID NOT NULL REFERENCES offers(id) ON DELETE CASCADE,
+    version                  INT NOT NULL,
+    proposed_by              UUID NOT NULL REFERENCES users(id),
+    proposer_role            VARCHAR(10) NOT NULL,  -- client | agent
+    price_lamports           BIGINT,
+    estimated_duration_hours INT,
+    max_revisions            INT NOT NULL,
+    scope                    TEXT,
+    message                  TEXT,
+    status                   VARCHAR(20) NOT NULL DEFAULT 'proposed', -- proposed | agreed | superseded (by a later version, or withdrawn)
+    agreed_by                UUID REFERENCES users(id),
+    agreed_at                TIMESTAMPTZ,
+    created_at               TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(offer_id, version)
+);
 
 -- ─── Contracts ──────────────────────────────────────────────
 CREATE TABLE contracts (
@@ -110,28 +296,65 @@ CREATE TABLE contracts (
     agent_id             UUID NOT NULL REFERENCES agents(id),
     client_id            UUID NOT NULL REFERENCES users(id),
     agreed_price_lamports BIGINT NOT NULL,
-    status               VARCHAR(20) NOT NULL DEFAULT 'active', -- active | completed | disputed | cancelled
+    max_revisions        INT NOT NULL DEFAULT 2,
+    status               VARCHAR(20) NOT NULL DEFAULT 'active', -- active | completed | disputed | resolved | cancelled
+    due_at               TIMESTAMPTZ,   -- earlier of job deadline and acceptance + estimated duration
+    delivered_at         TIMESTAMPTZ,   -- first submission (last milestone delivered)
+    is_late              BOOLEAN,       -- NULL until judged by the deadline worker
+    battle_rank          INT,           -- battle prizes: 1 = winner, 2.. = rewarded runners-up
+    terms_version        INT,           -- offer revision the contract terms come from
+    scope                TEXT,          -- agreed scope, if negotiated
     created_at           TIMESTAMPTZ NOT NULL DEFAULT now(),
     completed_at         TIMESTAMPTZ
 );
 CREATE INDEX idx_contracts_job ON contracts(job_id);
+-- Backstop for accept/select-winner races: at most one live contract per job
+-- (per prize rank for battles with runner-up rewards)
+CREATE UNIQUE INDEX idx_contracts_job_live_rank ON contracts(job_id, COALESCE(battle_rank, 1)) WHERE status <> 'cancelled';
+CREATE INDEX idx_contracts_agent ON contracts(agent_id);
+
+-- ─── Milestones ─────────────────────────────────────────────
+CREATE TABLE milestones (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    offer_id        UUID NOT NULL REFERENCES offers(id) ON DELETE CASCADE,
+    contract_id     UUID REFERENCES contracts(id),  -- set when the offer is accepted
+    position        INT NOT NULL,
+    title           VARCHAR(300) NOT NULL,
+    amount_lamports BIGINT NOT NULL CHECK (amount_lamports > 0),
+    due_at          TIMESTAMPTZ,
+    state           VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | funded | submitted | released
+    funded_at       TIMESTAMPTZ,
+    submitted_at    TIMESTAMPTZ,
+    released_at     TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(offer_id, position)
+);
+CREATE INDEX idx_milestones_contract ON milestones(contract_id);
 
 -- ─── Escrow accounts ────────────────────────────────────────
+-- One per contract, or one per milestone tranche for milestone contracts
 CREATE TABLE escrow_accounts (
     id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-    contract_id     UUID NOT NULL REFERENCES contracts(id) UNIQUE,
+    contract_id     UUID NOT NULL REFERENCES contracts(id),
+    milestone_id    UUID REFERENCES milestones(id) UNIQUE,
     amount_lamports BIGINT NOT NULL,
-    state           VARCHAR(20) NOT NULL DEFAULT 'none', -- none | funded | locked | released | refunded
+    topup_due_lamports BIGINT NOT NULL DEFAULT 0,  -- amendment top-up the client has yet to fund; blocks release, cancelled on auto-release
+    state           VARCHAR(20) NOT NULL DEFAULT 'none', -- none | funded | locked | frozen | released | refunded | split
     funded_at       TIMESTAMPTZ,
+    locked_at       TIMESTAMPTZ,   -- review window starts here
+    reminder_sent_at TIMESTAMPTZ,
     released_at     TIMESTAMPTZ,
     created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
 );
+CREATE INDEX idx_escrow_locked ON escrow_accounts(locked_at) WHERE state = 'locked';
+CREATE INDEX idx_escrow_contract ON escrow_accounts(contract_id);
+CREATE UNIQUE INDEX idx_escrow_contract_single ON escrow_accounts(contract_id) WHERE milestone_id IS NULL;
 
 -- ─── Escrow ledger ──────────────────────────────────────────
 CREATE TABLE escrow_ledger_entries (
     id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
     escrow_id       UUID NOT NULL REFERENCES escrow_accounts(id),
-    entry_type      VARCHAR(30) NOT NULL, -- fund | lock | release | refund
+    entry_type      VARCHAR(30) NOT NULL, -- fund | lock | unlock | freeze | release | refund | cancel_topup
     amount_lamports BIGINT NOT NULL,
     created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
 );
@@ -145,8 +368,21 @@ CREATE TABLE job_runs (
     started_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
     finished_at  TIMESTAMPTZ,
     output_summary TEXT,
-    logs_url     VARCHAR(500)
+    logs_url     VARCHAR(500),
+    progress_pct INT           -- latest reported progress, 0-100
+);
+CREATE INDEX idx_job_runs_contract ON job_runs(contract_id, started_at);
+-- One run in flight per contract
+CREATE UNIQUE INDEX idx_job_runs_running ON job_runs(contract_id) WHERE status = 'running';
+
+CREATE TABLE job_run_events (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id       UUID NOT NULL REFERENCES job_runs(id) ON DELETE CASCADE,
+    message      TEXT NOT NULL,
+    progress_pct INT,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
 );
+CREATE INDEX idx_job_run_events_run ON job_run_events(run_id, created_at);
 
 -- ─── Submissions ────────────────────────────────────────────
 CREATE TABLE submissions (
@@ -156,11 +392,128 @@ CREATE TABLE submissions (
     agent_id            UUID NOT NULL REFERENCES agents(id),
     content             TEXT NOT NULL,
     artifacts_url       VARCHAR(500),
-    status              VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | accepted | rejected
+    status              VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | accepted | rejected | revision_requested
     is_battle_submission BOOLEAN NOT NULL DEFAULT FALSE,
+    content_hash        VARCHAR(64),   -- sha256 hex of content; all a sealed battle entry shows
+    rubric_score        REAL,          -- battle entries: weighted judge score 0-100
+    rubric_rank         INT,           -- battle entries: 1 = best by rubric score
+    milestone_id        UUID REFERENCES milestones(id),
+    feedback            TEXT,          -- client's reason when rejecting
+    reviewed_at         TIMESTAMPTZ,
     created_at          TIMESTAMPTZ NOT NULL DEFAULT now()
 );
 CREATE INDEX idx_submissions_job ON submissions(job_id);
+CREATE INDEX idx_submissions_contract ON submissions(contract_id);
+
+-- ─── Job dispatches ─────────────────────────────────────────
+-- Outbox of signed job payloads pushed to agents' endpoint_url
+CREATE TABLE job_dispatches (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id     UUID NOT NULL REFERENCES contracts(id),
+    milestone_id    UUID REFERENCES milestones(id),
+    agent_id        UUID NOT NULL REFERENCES agents(id),
+    endpoint_url    VARCHAR(500) NOT NULL,
+    payload         JSONB NOT NULL,
+    status          VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | delivered | failed | completed
+    attempts        INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_error      TEXT,
+    delivered_at    TIMESTAMPTZ,
+    submission_id   UUID REFERENCES submissions(id),
+    completed_at    TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_job_dispatches_contract ON job_dispatches(contract_id);
+CREATE INDEX idx_job_dispatches_due ON job_dispatches(next_attempt_at) WHERE status = 'pending';
+
+-- ─── Submission checks ──────────────────────────────────────
+-- Outcome of each machine-checkable requirement, run when the submission was created
+CREATE TABLE submission_checks (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id  UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    requirement_id UUID NOT NULL REFERENCES job_requirements(id) ON DELETE CASCADE,
+    passed         BOOLEAN NOT NULL,
+    detail         TEXT,          -- why the check failed
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(submission_id, requirement_id)
+);
+
+-- ─── Battle judging ─────────────────────────────────────────
+-- Rubric criteria per battle job, seeded from job_requirements by default
+CREATE TABLE battle_rubric_criteria (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id         UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    requirement_id UUID REFERENCES job_requirements(id) ON DELETE SET NULL,
+    position       INT NOT NULL,
+    name           VARCHAR(300) NOT NULL,
+    description    TEXT,
+    weight         REAL NOT NULL DEFAULT 1.0 CHECK (weight > 0),
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(job_id, position)
+);
+
+-- Judges invited by the job owner (the owner always judges)
+CREATE TABLE battle_judges (
+    job_id      UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_by  UUID NOT NULL REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (job_id, user_id)
+);
+
+CREATE TABLE battle_scores (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    criterion_id  UUID NOT NULL REFERENCES battle_rubric_criteria(id) ON DELETE CASCADE,
+    judge_id      UUID NOT NULL REFERENCES users(id),
+    score         INT NOT NULL CHECK (score BETWEEN 0 AND 10),
+    comment       TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(submission_id, criterion_id, judge_id)
+);
+CREATE INDEX idx_battle_scores_submission ON battle_scores(submission_id);
+
+-- ─── Battle rating history ──────────────────────────────────
+-- One row per agent per decided battle; separate from the Lobster Score
+CREATE TABLE battle_rating_history (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id      UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    job_id        UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    placement     INT NOT NULL,   -- 1 = winner; unranked entrants share last place
+    entrants      INT NOT NULL,
+    rating_before REAL NOT NULL,
+    rating_after  REAL NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(agent_id, job_id)
+);
+CREATE INDEX idx_battle_rating_history_agent ON battle_rating_history(agent_id, created_at DESC);
+
+-- ─── Revision requests ──────────────────────────────────────
+CREATE TABLE revision_requests (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id   UUID NOT NULL REFERENCES contracts(id),
+    submission_id UUID NOT NULL REFERENCES submissions(id),
+    requested_by  UUID NOT NULL REFERENCES users(id),
+    round         INT NOT NULL,
+    changes       TEXT[] NOT NULL,
+    message       TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_revision_requests_contract ON revision_requests(contract_id);
+
+-- ─── Contract amendments ────────────────────────────────────
+CREATE TABLE contract_amendments (
+    id                        UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id               UUID NOT NULL REFERENCES contracts(id),
+    proposed_by               UUID NOT NULL REFERENCES users(id),
+    additional_revisions      INT NOT NULL DEFAULT 0,
+    additional_price_lamports BIGINT NOT NULL DEFAULT 0,
+    status                    VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | accepted | declined
+    created_at                TIMESTAMPTZ NOT NULL DEFAULT now(),
+    decided_at                TIMESTAMPTZ
+);
+CREATE INDEX idx_contract_amendments_contract ON contract_amendments(contract_id);
 
 -- ─── Reviews ────────────────────────────────────────────────
 CREATE TABLE reviews (
@@ -233,12 +586,40 @@ CREATE TABLE disputes (
     contract_id   UUID NOT NULL REFERENCES contracts(id),
     initiator_id  UUID NOT NULL REFERENCES users(id),
     reason        TEXT NOT NULL,
+    evidence_links TEXT[] DEFAULT '{}',
     status        VARCHAR(20) NOT NULL DEFAULT 'open', -- open | under_review | resolved | dismissed
+    outcome       VARCHAR(20),  -- release | refund | split
+    agent_payout_pct INT CHECK (agent_payout_pct BETWEEN 0 AND 100),
     resolution    TEXT,
     resolved_by   UUID REFERENCES users(id),
     created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
     resolved_at   TIMESTAMPTZ
 );
+CREATE INDEX idx_disputes_contract ON disputes(contract_id);
+
+-- ─── Dispute statements ─────────────────────────────────────
+CREATE TABLE dispute_statements (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    dispute_id     UUID NOT NULL REFERENCES disputes(id) ON DELETE CASCADE,
+    author_id      UUID NOT NULL REFERENCES users(id),
+    author_role    VARCHAR(20) NOT NULL, -- client | agent | moderator
+    body           TEXT NOT NULL,
+    evidence_links TEXT[] DEFAULT '{}',
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_dispute_statements_dispute ON dispute_statements(dispute_id);
+
+-- ─── Notifications ──────────────────────────────────────────
+CREATE TABLE notifications (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind        VARCHAR(50) NOT NULL,
+    message     TEXT NOT NULL,
+    metadata    JSONB DEFAULT '{}',
+    read_at     TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_notifications_user ON notifications(user_id, created_at DESC);
 
 -- ─── Favorites ──────────────────────────────────────────────
 CREATE TABLE favorites (
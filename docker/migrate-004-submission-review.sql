@@ -0,0 +1,10 @@
+-- Migration 004: Work submission + client approval for regular contracts
+-- Safe to re-run.
+
+-- Client feedback on a reviewed submission
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS feedback TEXT;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS reviewed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_contract ON submissions(contract_id);
+
+-- escrow_ledger_entries.entry_type gains 'unlock' (locked → funded on rejection)
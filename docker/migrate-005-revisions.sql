@@ -0,0 +1,32 @@
+-- Migration 005: Revision requests with bounded rounds + contract amendments
+-- Safe to re-run.
+
+-- Revision rounds agreed in the offer, copied onto the contract at acceptance
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS max_revisions INT NOT NULL DEFAULT 2;
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS max_revisions INT NOT NULL DEFAULT 2;
+
+-- submissions.status gains 'revision_requested'
+
+CREATE TABLE IF NOT EXISTS revision_requests (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id   UUID NOT NULL REFERENCES contracts(id),
+    submission_id UUID NOT NULL REFERENCES submissions(id),
+    requested_by  UUID NOT NULL REFERENCES users(id),
+    round         INT NOT NULL,
+    changes       TEXT[] NOT NULL,
+    message       TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_revision_requests_contract ON revision_requests(contract_id);
+
+CREATE TABLE IF NOT EXISTS contract_amendments (
+    id                        UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id               UUID NOT NULL REFERENCES contracts(id),
+    proposed_by               UUID NOT NULL REFERENCES users(id),
+    additional_revisions      INT NOT NULL DEFAULT 0,
+    additional_price_lamports BIGINT NOT NULL DEFAULT 0,
+    status                    VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | accepted | declined
+    created_at                TIMESTAMPTZ NOT NULL DEFAULT now(),
+    decided_at                TIMESTAMPTZ
+);
+CREATE INDEX IF NOT EXISTS idx_contract_amendments_contract ON contract_amendments(contract_id);
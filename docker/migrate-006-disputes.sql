@@ -0,0 +1,18 @@
+-- Migration 006: Dispute filing + statement threads
+-- Safe to re-run.
+
+ALTER TABLE disputes ADD COLUMN IF NOT EXISTS evidence_links TEXT[] DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS idx_disputes_contract ON disputes(contract_id);
+
+-- escrow_accounts.state gains 'frozen'; escrow_ledger_entries.entry_type gains 'freeze'
+
+CREATE TABLE IF NOT EXISTS dispute_statements (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    dispute_id     UUID NOT NULL REFERENCES disputes(id) ON DELETE CASCADE,
+    author_id      UUID NOT NULL REFERENCES users(id),
+    author_role    VARCHAR(20) NOT NULL, -- client | agent | moderator
+    body           TEXT NOT NULL,
+    evidence_links TEXT[] DEFAULT '{}',
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_dispute_statements_dispute ON dispute_statements(dispute_id);
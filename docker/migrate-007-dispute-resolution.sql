@@ -0,0 +1,7 @@
+-- Migration 007: Admin dispute resolution with split payouts
+-- Safe to re-run.
+
+ALTER TABLE disputes ADD COLUMN IF NOT EXISTS outcome VARCHAR(20);  -- release | refund | split
+ALTER TABLE disputes ADD COLUMN IF NOT EXISTS agent_payout_pct INT CHECK (agent_payout_pct BETWEEN 0 AND 100);
+
+-- escrow_accounts.state gains 'split'; contracts.status gains 'resolved'
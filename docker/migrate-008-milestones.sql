@@ -0,0 +1,27 @@
+-- Migration 008: Milestone contracts with per-milestone escrow tranches
+-- Safe to re-run.
+
+CREATE TABLE IF NOT EXISTS milestones (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    offer_id        UUID NOT NULL REFERENCES offers(id) ON DELETE CASCADE,
+    contract_id     UUID REFERENCES contracts(id),  -- set when the offer is accepted
+    position        INT NOT NULL,
+    title           VARCHAR(300) NOT NULL,
+    amount_lamports BIGINT NOT NULL CHECK (amount_lamports > 0),
+    due_at          TIMESTAMPTZ,
+    state           VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | funded | submitted | released
+    funded_at       TIMESTAMPTZ,
+    submitted_at    TIMESTAMPTZ,
+    released_at     TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(offer_id, position)
+);
+CREATE INDEX IF NOT EXISTS idx_milestones_contract ON milestones(contract_id);
+
+-- Escrow is no longer 1:1 with contracts: one account per milestone tranche
+ALTER TABLE escrow_accounts DROP CONSTRAINT IF EXISTS escrow_accounts_contract_id_key;
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS milestone_id UUID REFERENCES milestones(id) UNIQUE;
+CREATE INDEX IF NOT EXISTS idx_escrow_contract ON escrow_accounts(contract_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_escrow_contract_single ON escrow_accounts(contract_id) WHERE milestone_id IS NULL;
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS milestone_id UUID REFERENCES milestones(id);
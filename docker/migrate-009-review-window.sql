@@ -0,0 +1,20 @@
+-- Migration 009: Review window auto-release + notifications
+-- Safe to re-run.
+
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS locked_at TIMESTAMPTZ;
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS reminder_sent_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS idx_escrow_locked ON escrow_accounts(locked_at) WHERE state = 'locked';
+
+-- Escrow already locked before this migration starts its review window now
+UPDATE escrow_accounts SET locked_at = now() WHERE state = 'locked' AND locked_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind        VARCHAR(50) NOT NULL,
+    message     TEXT NOT NULL,
+    metadata    JSONB DEFAULT '{}',
+    read_at     TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- Migration 010: Deadline enforcement + real on-time delivery rate
+-- Safe to re-run.
+
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS due_at TIMESTAMPTZ;
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS delivered_at TIMESTAMPTZ;
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS is_late BOOLEAN;
+CREATE INDEX IF NOT EXISTS idx_contracts_agent ON contracts(agent_id);
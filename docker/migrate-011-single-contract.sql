@@ -0,0 +1,6 @@
+-- Migration 011: At most one live contract per job
+-- Backstop for concurrent offer acceptance / battle winner selection.
+-- Fails if duplicates already exist; resolve those (cancel the extra contract) first.
+-- Safe to re-run.
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contracts_job_live ON contracts(job_id) WHERE status <> 'cancelled';
@@ -0,0 +1,15 @@
+-- Migration 012: Job run reporting (progress timeline)
+-- Safe to re-run.
+
+ALTER TABLE job_runs ADD COLUMN IF NOT EXISTS progress_pct INT;
+CREATE INDEX IF NOT EXISTS idx_job_runs_contract ON job_runs(contract_id, started_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_job_runs_running ON job_runs(contract_id) WHERE status = 'running';
+
+CREATE TABLE IF NOT EXISTS job_run_events (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id       UUID NOT NULL REFERENCES job_runs(id) ON DELETE CASCADE,
+    message      TEXT NOT NULL,
+    progress_pct INT,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_job_run_events_run ON job_run_events(run_id, created_at);
@@ -0,0 +1,23 @@
+-- Migration 013: Push dispatch of funded contracts to agent endpoints
+-- Safe to re-run.
+
+ALTER TABLE agents ADD COLUMN IF NOT EXISTS dispatch_secret VARCHAR(64);
+
+CREATE TABLE IF NOT EXISTS job_dispatches (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id     UUID NOT NULL REFERENCES contracts(id),
+    milestone_id    UUID REFERENCES milestones(id),
+    agent_id        UUID NOT NULL REFERENCES agents(id),
+    endpoint_url    VARCHAR(500) NOT NULL,
+    payload         JSONB NOT NULL,
+    status          VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | delivered | failed | completed
+    attempts        INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_error      TEXT,
+    delivered_at    TIMESTAMPTZ,
+    submission_id   UUID REFERENCES submissions(id),
+    completed_at    TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_job_dispatches_contract ON job_dispatches(contract_id);
+CREATE INDEX IF NOT EXISTS idx_job_dispatches_due ON job_dispatches(next_attempt_at) WHERE status = 'pending';
@@ -0,0 +1,13 @@
+-- Migration 015: Agent endpoint health samples
+-- Safe to re-run.
+
+CREATE TABLE IF NOT EXISTS agent_health_checks (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id    UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    is_up       BOOLEAN NOT NULL,
+    latency_ms  INT,
+    status_code INT,
+    error       TEXT,
+    checked_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_agent_health_checks_agent ON agent_health_checks(agent_id, checked_at DESC);
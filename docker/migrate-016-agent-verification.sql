@@ -0,0 +1,19 @@
+-- Migration 016: Agent ownership verification
+-- Safe to re-run.
+
+ALTER TABLE agents ADD COLUMN IF NOT EXISTS verified_until TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS agent_verifications (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id    UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    method      VARCHAR(20) NOT NULL,  -- endpoint | wallet
+    token       VARCHAR(64) NOT NULL,
+    status      VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending | verified | failed | expired
+    wallet      VARCHAR(64),
+    last_error  TEXT,
+    expires_at  TIMESTAMPTZ NOT NULL,
+    verified_at TIMESTAMPTZ,
+    reminder_sent_at TIMESTAMPTZ,      -- owner asked to renew a wallet verification
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_agent_verifications_agent ON agent_verifications(agent_id, created_at DESC);
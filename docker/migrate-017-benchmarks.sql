@@ -0,0 +1,43 @@
+-- Migration 017: Benchmark tasks, runs and results ('proved' tier)
+-- Safe to re-run.
+
+CREATE TABLE IF NOT EXISTS benchmark_tasks (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    capability      VARCHAR(100) NOT NULL,
+    name            VARCHAR(200) NOT NULL,
+    input           JSONB NOT NULL,
+    expected_output JSONB NOT NULL,
+    scoring         VARCHAR(20) NOT NULL DEFAULT 'exact', -- exact | contains | json_subset | numeric
+    tolerance       DOUBLE PRECISION,                     -- numeric scoring only
+    weight          REAL NOT NULL DEFAULT 1.0,
+    is_active       BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by      UUID REFERENCES users(id),
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_benchmark_tasks_capability ON benchmark_tasks(capability) WHERE is_active;
+
+CREATE TABLE IF NOT EXISTS benchmark_runs (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id     UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    capability   VARCHAR(100) NOT NULL,
+    status       VARCHAR(20) NOT NULL DEFAULT 'running', -- running | completed | failed
+    score        REAL,            -- weighted 0-100
+    passed       BOOLEAN,
+    task_count   INT NOT NULL DEFAULT 0,
+    started_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at  TIMESTAMPTZ
+);
+CREATE INDEX IF NOT EXISTS idx_benchmark_runs_agent ON benchmark_runs(agent_id, capability, started_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_benchmark_runs_running ON benchmark_runs(agent_id, capability) WHERE status = 'running';
+
+CREATE TABLE IF NOT EXISTS benchmark_results (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id      UUID NOT NULL REFERENCES benchmark_runs(id) ON DELETE CASCADE,
+    task_id     UUID NOT NULL REFERENCES benchmark_tasks(id),
+    output      JSONB,
+    score       REAL NOT NULL,    -- 0-1
+    latency_ms  INT,
+    error       TEXT,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_benchmark_results_run ON benchmark_results(run_id);
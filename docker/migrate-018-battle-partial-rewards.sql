@@ -0,0 +1,9 @@
+-- Migration 018: Battle runner-up rewards
+-- Runners-up ranked by the client get their own prize contract, so the
+-- one-live-contract-per-job backstop becomes one per job and prize rank.
+-- Safe to re-run.
+
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS battle_rank INT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contracts_job_live_rank ON contracts(job_id, COALESCE(battle_rank, 1)) WHERE status <> 'cancelled';
+DROP INDEX IF EXISTS idx_contracts_job_live;
@@ -0,0 +1,11 @@
+-- Migration 019: Battle close time and sealed submissions
+-- Safe to re-run.
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS battle_closes_at TIMESTAMPTZ;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS content_hash VARCHAR(64);
+
+UPDATE submissions
+SET content_hash = encode(sha256(convert_to(content, 'UTF8')), 'hex')
+WHERE is_battle_submission AND content_hash IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_battle_closes ON jobs(battle_closes_at) WHERE battle_mode AND state = 'open';
@@ -0,0 +1,40 @@
+-- Migration 020: Battle judging rubric, invited judges and per-criterion scores
+-- Safe to re-run.
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS rubric_score REAL;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS rubric_rank INT;
+
+-- Rubric criteria per battle job, seeded from job_requirements by default
+CREATE TABLE IF NOT EXISTS battle_rubric_criteria (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id         UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    requirement_id UUID REFERENCES job_requirements(id) ON DELETE SET NULL,
+    position       INT NOT NULL,
+    name           VARCHAR(300) NOT NULL,
+    description    TEXT,
+    weight         REAL NOT NULL DEFAULT 1.0 CHECK (weight > 0),
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(job_id, position)
+);
+
+-- Judges invited by the job owner (the owner always judges)
+CREATE TABLE IF NOT EXISTS battle_judges (
+    job_id      UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_by  UUID NOT NULL REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (job_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS battle_scores (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    criterion_id  UUID NOT NULL REFERENCES battle_rubric_criteria(id) ON DELETE CASCADE,
+    judge_id      UUID NOT NULL REFERENCES users(id),
+    score         INT NOT NULL CHECK (score BETWEEN 0 AND 10),
+    comment       TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(submission_id, criterion_id, judge_id)
+);
+CREATE INDEX IF NOT EXISTS idx_battle_scores_submission ON battle_scores(submission_id);
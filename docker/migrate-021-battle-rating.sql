@@ -0,0 +1,20 @@
+-- Migration 021: Elo-style battle rating and its history
+-- Safe to re-run.
+
+ALTER TABLE agents ADD COLUMN IF NOT EXISTS battle_rating REAL NOT NULL DEFAULT 1500.0;
+ALTER TABLE agents ADD COLUMN IF NOT EXISTS battles_played INT NOT NULL DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_agents_battle_rating ON agents(battle_rating DESC) WHERE battles_played > 0;
+
+-- One row per agent per decided battle; separate from the Lobster Score
+CREATE TABLE IF NOT EXISTS battle_rating_history (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    agent_id      UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    job_id        UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    placement     INT NOT NULL,   -- 1 = winner; unranked entrants share last place
+    entrants      INT NOT NULL,
+    rating_before REAL NOT NULL,
+    rating_after  REAL NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(agent_id, job_id)
+);
+CREATE INDEX IF NOT EXISTS idx_battle_rating_history_agent ON battle_rating_history(agent_id, created_at DESC);
@@ -0,0 +1,16 @@
+-- Migration 022: Machine-checkable job requirements and per-submission results
+-- Safe to re-run.
+
+ALTER TABLE job_requirements ADD COLUMN IF NOT EXISTS check_type VARCHAR(20);
+ALTER TABLE job_requirements ADD COLUMN IF NOT EXISTS check_config JSONB;
+
+-- Outcome of each machine-checkable requirement, run when the submission was created
+CREATE TABLE IF NOT EXISTS submission_checks (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id  UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    requirement_id UUID NOT NULL REFERENCES job_requirements(id) ON DELETE CASCADE,
+    passed         BOOLEAN NOT NULL,
+    detail         TEXT,          -- why the check failed
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(submission_id, requirement_id)
+);
@@ -0,0 +1,34 @@
+-- Migration 023: Offer negotiation threads (versioned counter-offers)
+-- Safe to re-run.
+
+CREATE TABLE IF NOT EXISTS offer_revisions (
+    id                       UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    offer_id                 UUID NOT NULL REFERENCES offers(id) ON DELETE CASCADE,
+    version                  INT NOT NULL,
+    proposed_by              UUID NOT NULL REFERENCES users(id),
+    proposer_role            VARCHAR(10) NOT NULL,  -- client | agent
+    price_lamports           BIGINT,
+    estimated_duration_hours INT,
+    max_revisions            INT NOT NULL,
+    scope                    TEXT,
+    message                  TEXT,
+    status                   VARCHAR(20) NOT NULL DEFAULT 'proposed', -- proposed | agreed | superseded
+    agreed_by                UUID REFERENCES users(id),
+    agreed_at                TIMESTAMPTZ,
+    created_at               TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(offer_id, version)
+);
+
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS terms_version INT;
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS scope TEXT;
+
+-- Existing non-battle offers start their thread at version 1
+INSERT INTO offer_revisions (offer_id, version, proposed_by, proposer_role, price_lamports,
+                             estimated_duration_hours, max_revisions, status, created_at)
+SELECT o.id, 1, a.owner_id, 'agent', o.proposed_price_lamports, o.estimated_duration_hours, o.max_revisions,
+       CASE WHEN o.status = 'accepted' THEN 'agreed' ELSE 'proposed' END, o.created_at
+FROM offers o
+JOIN agents a ON a.id = o.agent_id
+JOIN jobs j ON j.id = o.job_id
+WHERE NOT j.battle_mode
+ON CONFLICT (offer_id, version) DO NOTHING;
@@ -0,0 +1,5 @@
+-- Migration 024: Offer validity period and automatic expiry
+-- Safe to re-run.
+
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS idx_offers_expiry ON offers(expires_at) WHERE status = 'pending';
@@ -0,0 +1,14 @@
+-- Migration 025: Job visibility, agent invites and direct hire
+-- Safe to re-run.
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS visibility VARCHAR(20) NOT NULL DEFAULT 'public';
+
+CREATE TABLE IF NOT EXISTS job_invites (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id      UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    agent_id    UUID NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    invited_by  UUID NOT NULL REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(job_id, agent_id)
+);
+CREATE INDEX IF NOT EXISTS idx_job_invites_agent ON job_invites(agent_id);
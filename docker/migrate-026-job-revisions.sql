@@ -0,0 +1,15 @@
+-- Migration 026: Editable jobs with revision history
+-- Safe to re-run.
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS edited_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS job_revisions (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id       UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    revision     INT NOT NULL,
+    edited_by    UUID NOT NULL REFERENCES users(id),
+    changes      JSONB NOT NULL,
+    is_material  BOOLEAN NOT NULL DEFAULT FALSE,  -- description, budget or deadline changed
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE(job_id, revision)
+);
@@ -0,0 +1,39 @@
+-- Migration 027: Job templates and recurring job schedules
+-- Safe to re-run.
+
+CREATE TABLE IF NOT EXISTS job_templates (
+    id                        UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_id                  UUID NOT NULL REFERENCES users(id),
+    name                      VARCHAR(100) NOT NULL,
+    title                     VARCHAR(300) NOT NULL,
+    description               TEXT NOT NULL,
+    budget_lamports           BIGINT,
+    currency                  VARCHAR(10) NOT NULL DEFAULT 'USDC',
+    currency_chain            VARCHAR(20) NOT NULL DEFAULT 'solana',
+    battle_mode               BOOLEAN NOT NULL DEFAULT FALSE,
+    battle_max_submissions    INT,
+    battle_partial_reward_pct INT,
+    battle_duration_hours     INT,           -- battle_closes_at = creation + this
+    deadline_hours            INT,           -- deadline = creation + this
+    tags                      TEXT[] DEFAULT '{}',
+    visibility                VARCHAR(20) NOT NULL DEFAULT 'public',
+    requirements              JSONB NOT NULL DEFAULT '[]', -- [{requirement, is_mandatory, check_type, check_config}]
+    created_at                TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_job_templates_owner ON job_templates(owner_id);
+
+-- Recurring jobs: a worker creates (and optionally publishes) a job from the template on each cron tick.
+CREATE TABLE IF NOT EXISTS job_schedules (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id   UUID NOT NULL REFERENCES job_templates(id) ON DELETE CASCADE,
+    owner_id      UUID NOT NULL REFERENCES users(id),
+    cron          VARCHAR(100) NOT NULL,   -- 5-field cron in UTC, or @hourly | @daily | @weekly | @monthly
+    auto_publish  BOOLEAN NOT NULL DEFAULT FALSE,
+    is_active     BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at   TIMESTAMPTZ,
+    last_run_at   TIMESTAMPTZ,
+    last_job_id   UUID REFERENCES jobs(id) ON DELETE SET NULL,
+    last_error    TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_job_schedules_due ON job_schedules(next_run_at) WHERE is_active;
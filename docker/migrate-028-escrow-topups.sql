@@ -0,0 +1,4 @@
+-- Migration 028: Amendment top-ups on funded escrow wait for the client to fund them
+-- Safe to re-run.
+
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS topup_due_lamports BIGINT NOT NULL DEFAULT 0;